@@ -0,0 +1,312 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// How an `Option` field is encoded.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum OptionEncoding {
+    /// A list of zero or one item, which is how `Option` implements `Encodable`.
+    List,
+    /// `#[rlp(option = "empty_data")]`
+    EmptyData,
+    /// `#[rlp(option = "trailing")]`, or `#[rlp(trailing)]` on an `Option` field
+    Trailing,
+}
+
+/// The `#[rlp(...)]` attributes of a field.
+pub struct FieldAttrs {
+    pub option: OptionEncoding,
+    /// The value of a field omitted by `#[rlp(skip)]`.
+    pub skip: Option<TokenStream>,
+    /// Whether the field can be absent at the end of the list.
+    pub trailing: bool,
+    /// `fn(&T, &mut RlpStream)` appending the field as one item, set by `#[rlp(with)]` or `#[rlp(append_with)]`.
+    pub append_with: Option<syn::Path>,
+    /// `fn(&Rlp) -> Result<T, DecoderError>` decoding the field, set by `#[rlp(with)]` or `#[rlp(decode_with)]`.
+    pub decode_with: Option<syn::Path>,
+}
+
+pub fn field_attrs(field: &syn::Field) -> syn::Result<FieldAttrs> {
+    let mut attrs = FieldAttrs {
+        option: OptionEncoding::List,
+        skip: None,
+        trailing: false,
+        append_with: None,
+        decode_with: None,
+    };
+    let mut skip = false;
+    let mut default = None;
+    for meta in rlp_metas(&field.attrs)? {
+        match meta {
+            syn::Meta::Word(ref ident) if ident == "skip" => skip = true,
+            syn::Meta::Word(ref ident) if ident == "trailing" => attrs.trailing = true,
+            syn::Meta::Word(ref ident) if ident == "default" => default = Some(quote! { Default::default() }),
+            syn::Meta::NameValue(ref name_value) if name_value.ident == "default" => {
+                let expr: syn::Expr = match syn::parse_str(&lit_str(&name_value.lit)?) {
+                    Ok(expr) => expr,
+                    Err(err) => {
+                        return Err(syn::Error::new_spanned(
+                            &name_value.lit,
+                            format!("invalid expression in #[rlp(default = ...)]: {}", err),
+                        ))
+                    }
+                };
+                default = Some(quote! { #expr });
+            }
+            syn::Meta::NameValue(ref name_value) if name_value.ident == "with" => {
+                let module = lit_path(&name_value.lit)?;
+                attrs.append_with = Some(syn::parse_quote! { #module::append });
+                attrs.decode_with = Some(syn::parse_quote! { #module::decode });
+            }
+            syn::Meta::NameValue(ref name_value) if name_value.ident == "append_with" => {
+                attrs.append_with = Some(lit_path(&name_value.lit)?);
+            }
+            syn::Meta::NameValue(ref name_value) if name_value.ident == "decode_with" => {
+                attrs.decode_with = Some(lit_path(&name_value.lit)?);
+            }
+            syn::Meta::NameValue(ref name_value) if name_value.ident == "option" => {
+                attrs.option = match lit_str(&name_value.lit)?.as_str() {
+                    "empty_data" => OptionEncoding::EmptyData,
+                    "trailing" => OptionEncoding::Trailing,
+                    other => {
+                        return Err(syn::Error::new_spanned(
+                            &name_value.lit,
+                            format!("unknown #[rlp(option = \"{}\")], expected \"empty_data\" or \"trailing\"", other),
+                        ))
+                    }
+                };
+            }
+            ref other => return Err(syn::Error::new_spanned(other, "unknown #[rlp(...)] field attribute")),
+        }
+    }
+    let with = attrs.append_with.is_some() || attrs.decode_with.is_some();
+    if with && attrs.option != OptionEncoding::List {
+        return Err(syn::Error::new_spanned(field, "#[rlp(with)] cannot be combined with #[rlp(option = ...)]"))
+    }
+    // `Option` fields are omitted when they are `None`, and the others are always encoded.
+    if attrs.trailing && !with && attrs.option == OptionEncoding::List && is_option(&field.ty) {
+        attrs.option = OptionEncoding::Trailing;
+    }
+    if attrs.option == OptionEncoding::Trailing {
+        attrs.trailing = true;
+    }
+    if skip {
+        if attrs.option != OptionEncoding::List || attrs.trailing || with {
+            return Err(syn::Error::new_spanned(
+                field,
+                "#[rlp(skip)] cannot be combined with other #[rlp(...)] field attributes",
+            ))
+        }
+        attrs.skip = Some(default.unwrap_or_else(|| quote! { Default::default() }));
+    } else if default.is_some() {
+        return Err(syn::Error::new_spanned(field, "#[rlp(default)] requires #[rlp(skip)]"))
+    }
+    Ok(attrs)
+}
+
+/// The attributes of every field.
+pub fn fields_attrs(fields: &syn::Fields) -> syn::Result<Vec<FieldAttrs>> {
+    fields.iter().map(field_attrs).collect()
+}
+
+/// The generics of the type, with `bound` required of every type parameter.
+///
+/// `#[rlp(bound = "...")]` on the type replaces the required bounds with the given where predicates.
+pub fn bounded_generics(ast: &syn::DeriveInput, bound: syn::Path) -> syn::Result<syn::Generics> {
+    let mut predicates = None;
+    for meta in rlp_metas(&ast.attrs)? {
+        match meta {
+            syn::Meta::NameValue(ref name_value) if name_value.ident == "bound" => {
+                let source = format!("where {}", lit_str(&name_value.lit)?);
+                let where_clause: syn::WhereClause = match syn::parse_str(&source) {
+                    Ok(where_clause) => where_clause,
+                    Err(err) => {
+                        return Err(syn::Error::new_spanned(
+                            &name_value.lit,
+                            format!("invalid where predicates in #[rlp(bound = ...)]: {}", err),
+                        ))
+                    }
+                };
+                predicates = Some(where_clause.predicates.into_iter().collect());
+            }
+            ref other => return Err(syn::Error::new_spanned(other, "unknown #[rlp(...)] type attribute")),
+        }
+    }
+
+    let mut generics = ast.generics.clone();
+    let predicates: Vec<syn::WherePredicate> = predicates.unwrap_or_else(|| {
+        generics
+            .type_params()
+            .map(|param| {
+                let ident = &param.ident;
+                syn::parse_quote! { #ident: #bound }
+            })
+            .collect()
+    });
+    generics.make_where_clause().predicates.extend(predicates);
+    Ok(generics)
+}
+
+/// The tag of every variant of an enum.
+///
+/// A variant without `#[rlp(tag = N)]` is tagged one more than the previous variant, or `0` if it is the first.
+pub fn variant_tags(body: &syn::DataEnum) -> syn::Result<Vec<u64>> {
+    let mut tags: Vec<u64> = Vec::new();
+    for variant in &body.variants {
+        let mut tag = None;
+        for meta in rlp_metas(&variant.attrs)? {
+            match meta {
+                syn::Meta::NameValue(ref name_value) if name_value.ident == "tag" => {
+                    tag = match name_value.lit {
+                        syn::Lit::Int(ref int) => Some(int.value()),
+                        ref lit => {
+                            return Err(syn::Error::new_spanned(lit, "expected an integer in #[rlp(tag = ...)]"))
+                        }
+                    };
+                }
+                ref other => return Err(syn::Error::new_spanned(other, "unknown #[rlp(...)] variant attribute")),
+            }
+        }
+        let tag = match tag.or_else(|| tags.last().map_or(Some(0), |tag| tag.checked_add(1))) {
+            Some(tag) => tag,
+            None => {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    format!("the tag of {} overflows u64, give it #[rlp(tag = ...)]", variant.ident),
+                ))
+            }
+        };
+        if tags.contains(&tag) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                format!("the tag {} of {} is already used", tag, variant.ident),
+            ))
+        }
+        tags.push(tag);
+    }
+    Ok(tags)
+}
+
+/// Checks that only the last encoded fields are trailing and returns the number of the other encoded fields.
+///
+/// Trailing `Option` fields must come after the other trailing fields, which are always encoded.
+pub fn leading_field_count(fields: &syn::Fields, attrs: &[FieldAttrs]) -> syn::Result<usize> {
+    let mut leading = 0;
+    let mut trailing = false;
+    let mut trailing_option = false;
+    for (field, attrs) in fields.iter().zip(attrs).filter(|(_, attrs)| attrs.skip.is_none()) {
+        if !attrs.trailing {
+            if trailing {
+                return Err(syn::Error::new_spanned(field, "only the last fields can be #[rlp(trailing)]"))
+            }
+            leading += 1;
+            continue
+        }
+        trailing = true;
+        if attrs.option == OptionEncoding::Trailing {
+            trailing_option = true;
+        } else if trailing_option {
+            return Err(syn::Error::new_spanned(
+                field,
+                "a trailing field that is not an Option cannot follow a trailing Option field",
+            ))
+        }
+    }
+    Ok(leading)
+}
+
+/// The only field of a struct given to a wrapper derive, which supports no `#[rlp(...)]` field attributes.
+pub fn wrapper_field<'a>(ast: &'a syn::DeriveInput, derive: &str) -> syn::Result<&'a syn::Field> {
+    let body = match ast.data {
+        syn::Data::Struct(ref s) => s,
+        syn::Data::Enum(ref e) => {
+            return Err(syn::Error::new_spanned(
+                e.enum_token,
+                format!("#[derive({})] is only defined for structs.", derive),
+            ))
+        }
+        syn::Data::Union(ref u) => {
+            return Err(syn::Error::new_spanned(
+                u.union_token,
+                format!("#[derive({})] is only defined for structs.", derive),
+            ))
+        }
+    };
+    let mut fields = body.fields.iter();
+    let field = match (fields.next(), fields.next()) {
+        (Some(field), None) => field,
+        _ => {
+            let message = format!("#[derive({})] is only defined for structs with one field.", derive);
+            return Err(match body.fields {
+                syn::Fields::Unit => syn::Error::new_spanned(&ast.ident, message),
+                _ => syn::Error::new_spanned(&body.fields, message),
+            })
+        }
+    };
+    if let Some(attr) = field.attrs.iter().find(|attr| is_rlp_attr(attr)) {
+        return Err(syn::Error::new_spanned(
+            attr,
+            format!("#[derive({})] does not support #[rlp(...)] field attributes", derive),
+        ))
+    }
+    Ok(field)
+}
+
+/// The items of every `#[rlp(...)]` attribute.
+fn rlp_metas(attrs: &[syn::Attribute]) -> syn::Result<Vec<syn::Meta>> {
+    let mut metas = Vec::new();
+    for attr in attrs {
+        if !is_rlp_attr(attr) {
+            continue
+        }
+        match attr.parse_meta() {
+            Ok(syn::Meta::List(list)) => {
+                for nested in list.nested {
+                    match nested {
+                        syn::NestedMeta::Meta(meta) => metas.push(meta),
+                        syn::NestedMeta::Literal(lit) => {
+                            return Err(syn::Error::new_spanned(lit, "unexpected literal in #[rlp(...)]"))
+                        }
+                    }
+                }
+            }
+            _ => return Err(syn::Error::new_spanned(attr, "expected #[rlp(...)]")),
+        }
+    }
+    Ok(metas)
+}
+
+fn is_option(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(path) => match path.path.segments.last() {
+            Some(segment) => segment.value().ident == "Option",
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+fn is_rlp_attr(attr: &syn::Attribute) -> bool {
+    attr.path.segments.len() == 1 && attr.path.segments[0].ident == "rlp"
+}
+
+fn lit_path(lit: &syn::Lit) -> syn::Result<syn::Path> {
+    match syn::parse_str(&lit_str(lit)?) {
+        Ok(path) => Ok(path),
+        Err(err) => Err(syn::Error::new_spanned(lit, format!("invalid path in #[rlp(...)]: {}", err))),
+    }
+}
+
+fn lit_str(lit: &syn::Lit) -> syn::Result<String> {
+    match lit {
+        syn::Lit::Str(s) => Ok(s.value()),
+        _ => Err(syn::Error::new_spanned(lit, "expected a string literal in #[rlp(...)]")),
+    }
+}
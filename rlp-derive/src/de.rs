@@ -14,19 +14,21 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
+use crate::attr::{
+    bounded_generics, field_attrs, fields_attrs, leading_field_count, variant_tags, wrapper_field, FieldAttrs,
+    OptionEncoding,
+};
 use proc_macro2::{Span, TokenStream};
 use quote::quote;
 
 struct ParseQuotes {
     single: TokenStream,
-    list: TokenStream,
     takes_index: bool,
 }
 
 fn decodable_parse_quotes() -> ParseQuotes {
     ParseQuotes {
         single: quote! { rlp.val_at },
-        list: quote! { rlp.list_at },
         takes_index: true,
     }
 }
@@ -34,62 +36,62 @@ fn decodable_parse_quotes() -> ParseQuotes {
 fn decodable_wrapper_parse_quotes() -> ParseQuotes {
     ParseQuotes {
         single: quote! { rlp.as_val },
-        list: quote! { rlp.as_list },
         takes_index: false,
     }
 }
 
-pub fn impl_decodable(ast: &syn::DeriveInput) -> TokenStream {
+pub fn impl_decodable(ast: &syn::DeriveInput) -> syn::Result<TokenStream> {
+    let name = &ast.ident;
     let body = match ast.data {
-        syn::Data::Struct(ref s) => s,
-        _ => panic!("#[derive(RlpDecodable)] is only defined for structs."),
+        syn::Data::Struct(ref s) => {
+            let stmts = decodable_fields(&s.fields, 0)?;
+            quote! {
+                let result = #name {
+                    #(#stmts)*
+                };
+            }
+        }
+        syn::Data::Enum(ref e) => decodable_enum(name, e)?,
+        syn::Data::Union(ref u) => {
+            return Err(syn::Error::new_spanned(
+                u.union_token,
+                "#[derive(RlpDecodable)] is only defined for structs and enums.",
+            ))
+        }
     };
 
-    let stmts: Vec<_> = body.fields.iter().enumerate().map(decodable_field_map).collect();
-    let name = &ast.ident;
-
+    let generics = bounded_generics(ast, syn::parse_quote!(rlp::Decodable))?;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     let dummy_const = syn::Ident::new(&format!("_IMPL_RLP_DECODABLE_FOR_{}", name), Span::call_site());
     let impl_block = quote! {
-        impl rlp::Decodable for #name {
+        impl #impl_generics rlp::Decodable for #name #ty_generics #where_clause {
             fn decode(rlp: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
-                let result = #name {
-                    #(#stmts)*
-                };
+                #body
 
                 Ok(result)
             }
         }
     };
 
-    quote! {
+    Ok(quote! {
         #[allow(non_upper_case_globals, unused_attributes, unused_qualifications)]
         const #dummy_const: () = {
             #impl_block
         };
-    }
+    })
 }
 
-pub fn impl_decodable_wrapper(ast: &syn::DeriveInput) -> TokenStream {
-    let body = match ast.data {
-        syn::Data::Struct(ref s) => s,
-        _ => panic!("#[derive(RlpDecodableWrapper)] is only defined for structs."),
-    };
-
-    let stmt = {
-        let fields: Vec<_> = body.fields.iter().collect();
-        if fields.len() == 1 {
-            let field = fields.first().expect("fields.len() == 1; qed");
-            decodable_field(0, field, decodable_wrapper_parse_quotes())
-        } else {
-            panic!("#[derive(RlpEncodableWrapper)] is only defined for structs with one field.")
-        }
-    };
+pub fn impl_decodable_wrapper(ast: &syn::DeriveInput) -> syn::Result<TokenStream> {
+    let field = wrapper_field(ast, "RlpDecodableWrapper")?;
+    let stmt = decodable_field(0, 0, field, &field_attrs(field)?, decodable_wrapper_parse_quotes());
 
     let name = &ast.ident;
 
+    let generics = bounded_generics(ast, syn::parse_quote!(rlp::Decodable))?;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     let dummy_const = syn::Ident::new(&format!("_IMPL_RLP_DECODABLE_FOR_{}", name), Span::call_site());
     let impl_block = quote! {
-        impl rlp::Decodable for #name {
+        impl #impl_generics rlp::Decodable for #name #ty_generics #where_clause {
             fn decode(rlp: &rlp::Rlp) -> Result<Self, rlp::DecoderError> {
                 let result = #name {
                     #stmt
@@ -100,47 +102,106 @@ pub fn impl_decodable_wrapper(ast: &syn::DeriveInput) -> TokenStream {
         }
     };
 
-    quote! {
+    Ok(quote! {
         #[allow(non_upper_case_globals, unused_attributes, unused_qualifications)]
         const #dummy_const: () = {
             #impl_block
         };
+    })
+}
+
+/// Unit-only enums are decoded from their tags, and the others from lists of the tag followed by the fields.
+fn decodable_enum(name: &syn::Ident, body: &syn::DataEnum) -> syn::Result<TokenStream> {
+    let tags = variant_tags(body)?;
+    let unit_only = body.variants.iter().all(|variant| variant.fields.iter().next().is_none());
+    let mut arms = Vec::new();
+    for (variant, tag) in body.variants.iter().zip(tags) {
+        let ident = &variant.ident;
+        arms.push(match variant.fields {
+            syn::Fields::Unit => quote! { #tag => #name::#ident, },
+            _ => {
+                let stmts = decodable_fields(&variant.fields, 1)?;
+                quote! { #tag => #name::#ident { #(#stmts)* }, }
+            }
+        });
     }
+    let tag = if unit_only {
+        quote! { rlp.as_val()? }
+    } else {
+        quote! { rlp.val_at(0)? }
+    };
+
+    Ok(quote! {
+        let tag: u64 = #tag;
+        let result = match tag {
+            #(#arms)*
+            tag => {
+                return Err(rlp::DecoderError::UnknownTag {
+                    type_name: stringify!(#name),
+                    tag,
+                })
+            }
+        };
+    })
 }
 
-fn decodable_field_map(tuple: (usize, &syn::Field)) -> TokenStream {
-    decodable_field(tuple.0, tuple.1, decodable_parse_quotes())
+/// The initializers of the fields, which are the items of the list from `offset` unless they are skipped.
+fn decodable_fields(fields: &syn::Fields, offset: usize) -> syn::Result<Vec<TokenStream>> {
+    let attrs = fields_attrs(fields)?;
+    leading_field_count(fields, &attrs)?;
+    let mut index = offset;
+    let mut stmts = Vec::new();
+    for (member, (field, attrs)) in fields.iter().zip(&attrs).enumerate() {
+        stmts.push(decodable_field(member, index, field, attrs, decodable_parse_quotes()));
+        if attrs.skip.is_none() {
+            index += 1;
+        }
+    }
+    Ok(stmts)
 }
 
-fn decodable_field(index: usize, field: &syn::Field, quotes: ParseQuotes) -> TokenStream {
+fn decodable_field(
+    member: usize,
+    index: usize,
+    field: &syn::Field,
+    attrs: &FieldAttrs,
+    quotes: ParseQuotes,
+) -> TokenStream {
     let id = match field.ident {
         Some(ref ident) => quote! { #ident },
         None => {
-            let index: syn::Index = index.into();
-            quote! { #index }
+            let member: syn::Index = member.into();
+            quote! { #member }
         }
     };
 
     let index = quote! { #index };
 
     let single = quotes.single;
-    let list = quotes.list;
 
-    match field.ty {
-        syn::Type::Path(ref path) => {
-            let ident = &path.path.segments.first().expect("there must be at least 1 segment").value().ident;
-            if &ident.to_string() == "Vec" {
+    if let Some(ref default) = attrs.skip {
+        return quote! { #id: #default, }
+    }
+
+    let value = if let Some(ref decode_with) = attrs.decode_with {
+        quote! { #decode_with(&rlp.at(#index)?)? }
+    } else {
+        match attrs.option {
+            OptionEncoding::EmptyData => quote! { #single::<rlp::OptionAsEmptyData<_>>(#index)?.0 },
+            OptionEncoding::Trailing => return quote! { #id: rlp::OptionAsTrailing::decode(rlp, #index)?, },
+            OptionEncoding::List => {
                 if quotes.takes_index {
-                    quote! { #id: #list(#index)?, }
+                    quote! { #single(#index)? }
                 } else {
-                    quote! { #id: #list()?, }
+                    quote! { #single()? }
                 }
-            } else if quotes.takes_index {
-                quote! { #id: #single(#index)?, }
-            } else {
-                quote! { #id: #single()?, }
             }
         }
-        _ => panic!("rlp_derive not supported"),
+    };
+
+    if attrs.trailing {
+        quote! { #id: if #index < rlp.item_count()? { #value } else { Default::default() }, }
+    } else {
+        quote! { #id: #value, }
     }
 }
@@ -14,110 +14,182 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
+use crate::attr::{
+    bounded_generics, field_attrs, fields_attrs, leading_field_count, variant_tags, wrapper_field, FieldAttrs,
+    OptionEncoding,
+};
 use proc_macro2::{Span, TokenStream};
 use quote::quote;
 
-pub fn impl_encodable(ast: &syn::DeriveInput) -> TokenStream {
+pub fn impl_encodable(ast: &syn::DeriveInput) -> syn::Result<TokenStream> {
+    let name = &ast.ident;
     let body = match ast.data {
-        syn::Data::Struct(ref s) => s,
-        _ => panic!("#[derive(RlpEncodable)] is only defined for structs."),
+        syn::Data::Struct(ref s) => {
+            let ids: Vec<_> = s.fields.iter().enumerate().map(|(index, field)| field_expr(index, field)).collect();
+            let (len, stmts) = encodable_fields(&s.fields, &ids)?;
+            quote! {
+                stream.begin_list(#len);
+                #(#stmts)*
+            }
+        }
+        syn::Data::Enum(ref e) => encodable_enum(name, e)?,
+        syn::Data::Union(ref u) => {
+            return Err(syn::Error::new_spanned(
+                u.union_token,
+                "#[derive(RlpEncodable)] is only defined for structs and enums.",
+            ))
+        }
     };
 
-    let stmts: Vec<_> = body.fields.iter().enumerate().map(encodable_field_map).collect();
-    let name = &ast.ident;
-
-    let stmts_len = stmts.len();
-    let stmts_len = quote! { #stmts_len };
+    let generics = bounded_generics(ast, syn::parse_quote!(rlp::Encodable))?;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     let dummy_const = syn::Ident::new(&format!("_IMPL_RLP_ENCODABLE_FOR_{}", name), Span::call_site());
     let impl_block = quote! {
-        impl rlp::Encodable for #name {
+        impl #impl_generics rlp::Encodable for #name #ty_generics #where_clause {
             fn rlp_append(&self, stream: &mut rlp::RlpStream) {
-                stream.begin_list(#stmts_len);
-                #(#stmts)*
+                #body
             }
         }
     };
 
-    quote! {
+    Ok(quote! {
         #[allow(non_upper_case_globals, unused_attributes, unused_qualifications)]
         const #dummy_const: () = {
             #impl_block
         };
-    }
+    })
 }
 
-pub fn impl_encodable_wrapper(ast: &syn::DeriveInput) -> TokenStream {
-    let body = match ast.data {
-        syn::Data::Struct(ref s) => s,
-        _ => panic!("#[derive(RlpEncodableWrapper)] is only defined for structs."),
-    };
-
-    let stmt = {
-        let fields: Vec<_> = body.fields.iter().collect();
-        if fields.len() == 1 {
-            let field = fields.first().expect("fields.len() == 1; qed");
-            encodable_field(0, field)
-        } else {
-            panic!("#[derive(RlpEncodableWrapper)] is only defined for structs with one field.")
-        }
-    };
+pub fn impl_encodable_wrapper(ast: &syn::DeriveInput) -> syn::Result<TokenStream> {
+    let field = wrapper_field(ast, "RlpEncodableWrapper")?;
+    let stmt = encodable_field(&field_expr(0, field), &field_attrs(field)?);
 
     let name = &ast.ident;
 
+    let generics = bounded_generics(ast, syn::parse_quote!(rlp::Encodable))?;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
     let dummy_const = syn::Ident::new(&format!("_IMPL_RLP_ENCODABLE_FOR_{}", name), Span::call_site());
     let impl_block = quote! {
-        impl rlp::Encodable for #name {
+        impl #impl_generics rlp::Encodable for #name #ty_generics #where_clause {
             fn rlp_append(&self, stream: &mut rlp::RlpStream) {
                 #stmt
             }
         }
     };
 
-    quote! {
+    Ok(quote! {
         #[allow(non_upper_case_globals, unused_attributes, unused_qualifications)]
         const #dummy_const: () = {
             #impl_block
         };
-    }
+    })
 }
 
-fn encodable_field_map(tuple: (usize, &syn::Field)) -> TokenStream {
-    encodable_field(tuple.0, tuple.1)
+/// Unit-only enums are encoded as their tags, and the others as lists of the tag followed by the fields.
+fn encodable_enum(name: &syn::Ident, body: &syn::DataEnum) -> syn::Result<TokenStream> {
+    let tags = variant_tags(body)?;
+    let unit_only = body.variants.iter().all(|variant| variant.fields.iter().next().is_none());
+    let mut arms = Vec::new();
+    for (variant, tag) in body.variants.iter().zip(tags) {
+        let ident = &variant.ident;
+        let bindings: Vec<_> = (0..variant.fields.iter().count())
+            .map(|index| syn::Ident::new(&format!("__field{}", index), Span::call_site()))
+            .collect();
+        let pattern = match variant.fields {
+            syn::Fields::Unit => quote! { #name::#ident },
+            _ => {
+                let members = variant.fields.iter().enumerate().map(field_member);
+                let attrs = fields_attrs(&variant.fields)?;
+                let bindings = attrs.iter().zip(&bindings).map(|(attrs, binding)| {
+                    if attrs.skip.is_some() {
+                        quote! { _ }
+                    } else {
+                        quote! { ref #binding }
+                    }
+                });
+                quote! { #name::#ident { #(#members: #bindings),* } }
+            }
+        };
+        if unit_only {
+            arms.push(quote! { #pattern => #tag, });
+            continue
+        }
+        let ids: Vec<_> = bindings.iter().map(|binding| quote! { (*#binding) }).collect();
+        let (len, stmts) = encodable_fields(&variant.fields, &ids)?;
+        arms.push(quote! {
+            #pattern => {
+                stream.begin_list(1 + #len);
+                stream.append(&#tag);
+                #(#stmts)*
+            }
+        });
+    }
+
+    if unit_only {
+        Ok(quote! {
+            let tag = match *self {
+                #(#arms)*
+            };
+            rlp::Encodable::rlp_append(&tag, stream);
+        })
+    } else {
+        Ok(quote! {
+            match *self {
+                #(#arms)*
+            }
+        })
+    }
 }
 
-fn encodable_field(index: usize, field: &syn::Field) -> TokenStream {
-    let ident = match field.ident {
+/// The number of items and the statements appending the fields not skipped, given the expression of each field.
+fn encodable_fields(fields: &syn::Fields, ids: &[TokenStream]) -> syn::Result<(TokenStream, Vec<TokenStream>)> {
+    let attrs = fields_attrs(fields)?;
+    let leading_len = leading_field_count(fields, &attrs)?;
+    let mut stmts = Vec::new();
+    let mut trailing = Vec::new();
+    for (attrs, id) in attrs.iter().zip(ids) {
+        if attrs.skip.is_some() {
+            continue
+        }
+        if attrs.option == OptionEncoding::Trailing {
+            trailing.push(quote! { #id.is_some() });
+        } else if attrs.trailing {
+            trailing.push(quote! { true });
+        }
+        stmts.push(encodable_field(id, attrs));
+    }
+
+    let len = if trailing.is_empty() {
+        quote! { #leading_len }
+    } else {
+        quote! { #leading_len + rlp::OptionAsTrailing::count(&[#(#trailing),*]) }
+    };
+    Ok((len, stmts))
+}
+fn field_member((index, field): (usize, &syn::Field)) -> TokenStream {
+    match field.ident {
         Some(ref ident) => quote! { #ident },
         None => {
             let index: syn::Index = index.into();
             quote! { #index }
         }
-    };
+    }
+}
 
-    let id = quote! { self.#ident };
-
-    match field.ty {
-        syn::Type::Path(ref path) => {
-            let top_segment = path.path.segments.first().expect("there must be at least 1 segment");
-            let ident = &top_segment.value().ident;
-            if &ident.to_string() == "Vec" {
-                let inner_ident = match top_segment.value().arguments {
-                    syn::PathArguments::AngleBracketed(ref angle) => {
-                        let ty = angle.args.first().expect("Vec has only one angle bracketed type; qed");
-                        match **ty.value() {
-                            syn::GenericArgument::Type(syn::Type::Path(ref path)) => {
-                                &path.path.segments.first().expect("there must be at least 1 segment").value().ident
-                            }
-                            _ => panic!("rlp_derive not supported"),
-                        }
-                    }
-                    _ => unreachable!("Vec has only one angle bracketed type; qed"),
-                };
-                quote! { stream.append_list::<#inner_ident, _>(&#id); }
-            } else {
-                quote! { stream.append(&#id); }
-            }
-        }
-        _ => panic!("rlp_derive not supported"),
+fn field_expr(index: usize, field: &syn::Field) -> TokenStream {
+    let member = field_member((index, field));
+    quote! { self.#member }
+}
+
+fn encodable_field(id: &TokenStream, attrs: &FieldAttrs) -> TokenStream {
+    if let Some(ref append_with) = attrs.append_with {
+        return quote! { #append_with(&#id, stream); }
     }
+    match attrs.option {
+        OptionEncoding::EmptyData => return quote! { rlp::OptionAsEmptyData::append(stream, &#id); },
+        OptionEncoding::Trailing => return quote! { rlp::OptionAsTrailing::append(stream, &#id); },
+        OptionEncoding::List => {}
+    }
+
+    quote! { stream.append(&#id); }
 }
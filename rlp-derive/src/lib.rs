@@ -16,37 +16,60 @@
 
 extern crate proc_macro;
 
+mod attr;
 mod de;
 mod en;
+mod schema;
 
 use de::{impl_decodable, impl_decodable_wrapper};
 use en::{impl_encodable, impl_encodable_wrapper};
 use proc_macro::TokenStream;
+use schema::{impl_schema, impl_schema_wrapper};
+use syn::parse_macro_input;
 
-#[proc_macro_derive(RlpEncodable)]
+/// Encodes a struct as a list of its fields, and an enum as a list of the tag and the fields of the variant.
+/// An enum without fields is encoded as the tag alone.
+///
+/// The encoder panics if a `None` field with `#[rlp(option = "trailing")]` or `#[rlp(trailing)]`
+/// is followed by a `Some` field, since the `None` cannot be omitted.
+#[proc_macro_derive(RlpEncodable, attributes(rlp))]
 pub fn encodable(input: TokenStream) -> TokenStream {
-    let ast = syn::parse(input).unwrap();
-    let gen = impl_encodable(&ast);
+    let ast = parse_macro_input!(input as syn::DeriveInput);
+    let gen = impl_encodable(&ast).unwrap_or_else(|err| err.to_compile_error());
     gen.into()
 }
 
-#[proc_macro_derive(RlpEncodableWrapper)]
+#[proc_macro_derive(RlpEncodableWrapper, attributes(rlp))]
 pub fn encodable_wrapper(input: TokenStream) -> TokenStream {
-    let ast = syn::parse(input).unwrap();
-    let gen = impl_encodable_wrapper(&ast);
+    let ast = parse_macro_input!(input as syn::DeriveInput);
+    let gen = impl_encodable_wrapper(&ast).unwrap_or_else(|err| err.to_compile_error());
     gen.into()
 }
 
-#[proc_macro_derive(RlpDecodable)]
+#[proc_macro_derive(RlpDecodable, attributes(rlp))]
 pub fn decodable(input: TokenStream) -> TokenStream {
-    let ast = syn::parse(input).unwrap();
-    let gen = impl_decodable(&ast);
+    let ast = parse_macro_input!(input as syn::DeriveInput);
+    let gen = impl_decodable(&ast).unwrap_or_else(|err| err.to_compile_error());
     gen.into()
 }
 
-#[proc_macro_derive(RlpDecodableWrapper)]
+#[proc_macro_derive(RlpDecodableWrapper, attributes(rlp))]
 pub fn decodable_wrapper(input: TokenStream) -> TokenStream {
-    let ast = syn::parse(input).unwrap();
-    let gen = impl_decodable_wrapper(&ast);
+    let ast = parse_macro_input!(input as syn::DeriveInput);
+    let gen = impl_decodable_wrapper(&ast).unwrap_or_else(|err| err.to_compile_error());
+    gen.into()
+}
+
+#[proc_macro_derive(RlpSchema, attributes(rlp))]
+pub fn schema(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as syn::DeriveInput);
+    let gen = impl_schema(&ast).unwrap_or_else(|err| err.to_compile_error());
+    gen.into()
+}
+
+#[proc_macro_derive(RlpSchemaWrapper, attributes(rlp))]
+pub fn schema_wrapper(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as syn::DeriveInput);
+    let gen = impl_schema_wrapper(&ast).unwrap_or_else(|err| err.to_compile_error());
     gen.into()
 }
@@ -0,0 +1,93 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use crate::attr::{bounded_generics, fields_attrs, wrapper_field, OptionEncoding};
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+
+pub fn impl_schema(ast: &syn::DeriveInput) -> syn::Result<TokenStream> {
+    let body = match ast.data {
+        syn::Data::Struct(ref s) => s,
+        syn::Data::Enum(ref e) => {
+            return Err(syn::Error::new_spanned(e.enum_token, "#[derive(RlpSchema)] is only defined for structs."))
+        }
+        syn::Data::Union(ref u) => {
+            return Err(syn::Error::new_spanned(u.union_token, "#[derive(RlpSchema)] is only defined for structs."))
+        }
+    };
+    let attrs = fields_attrs(&body.fields)?;
+    if let Some((field, _)) = body.fields.iter().zip(&attrs).find(|(_, attrs)| attrs.trailing) {
+        return Err(syn::Error::new_spanned(field, "#[derive(RlpSchema)] does not support #[rlp(trailing)]"))
+    }
+    if let Some((field, _)) = body.fields.iter().zip(&attrs).find(|(_, attrs)| attrs.option != OptionEncoding::List) {
+        return Err(syn::Error::new_spanned(field, "#[derive(RlpSchema)] does not support #[rlp(option = ...)]"))
+    }
+
+    let items: Vec<_> = body
+        .fields
+        .iter()
+        .zip(&attrs)
+        .filter(|(_, attrs)| attrs.skip.is_none())
+        .map(|(field, attrs)| {
+            // The shape of a field with a custom codec is unknown.
+            if attrs.append_with.is_some() || attrs.decode_with.is_some() {
+                quote! { rlp::Schema::Any }
+            } else {
+                field_schema(field)
+            }
+        })
+        .collect();
+    let name = &ast.ident;
+
+    let generics = bounded_generics(ast, syn::parse_quote!(rlp::RlpSchema))?;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let dummy_const = syn::Ident::new(&format!("_IMPL_RLP_SCHEMA_FOR_{}", name), Span::call_site());
+    let impl_block = quote! {
+        impl #impl_generics rlp::RlpSchema for #name #ty_generics #where_clause {
+            fn rlp_schema() -> rlp::Schema {
+                rlp::Schema::List(vec![#(#items),*])
+            }
+        }
+    };
+
+    Ok(quote! {
+        #[allow(non_upper_case_globals, unused_attributes, unused_qualifications)]
+        const #dummy_const: () = {
+            #impl_block
+        };
+    })
+}
+
+pub fn impl_schema_wrapper(ast: &syn::DeriveInput) -> syn::Result<TokenStream> {
+    let item = field_schema(wrapper_field(ast, "RlpSchemaWrapper")?);
+
+    let name = &ast.ident;
+
+    let generics = bounded_generics(ast, syn::parse_quote!(rlp::RlpSchema))?;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let dummy_const = syn::Ident::new(&format!("_IMPL_RLP_SCHEMA_FOR_{}", name), Span::call_site());
+    let impl_block = quote! {
+        impl #impl_generics rlp::RlpSchema for #name #ty_generics #where_clause {
+            fn rlp_schema() -> rlp::Schema {
+                #item
+            }
+        }
+    };
+
+    Ok(quote! {
+        #[allow(non_upper_case_globals, unused_attributes, unused_qualifications)]
+        const #dummy_const: () = {
+            #impl_block
+        };
+    })
+}
+
+fn field_schema(field: &syn::Field) -> TokenStream {
+    let ty = &field.ty;
+    quote! { <#ty as rlp::RlpSchema>::rlp_schema() }
+}
@@ -14,15 +14,16 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
-use rlp::{decode, encode};
-use rlp_derive::{RlpDecodable, RlpDecodableWrapper, RlpEncodable, RlpEncodableWrapper};
+use rlp::{decode, encode, encode_list, DecoderError, Rlp, RlpSchema, RlpStream, Schema};
+use rlp_derive::{RlpDecodable, RlpDecodableWrapper, RlpEncodable, RlpEncodableWrapper, RlpSchema, RlpSchemaWrapper};
+use std::time::Duration;
 
-#[derive(Debug, PartialEq, RlpEncodable, RlpDecodable)]
+#[derive(Debug, PartialEq, RlpEncodable, RlpDecodable, RlpSchema)]
 struct Foo {
     a: String,
 }
 
-#[derive(Debug, PartialEq, RlpEncodableWrapper, RlpDecodableWrapper)]
+#[derive(Debug, PartialEq, RlpEncodableWrapper, RlpDecodableWrapper, RlpSchemaWrapper)]
 struct FooWrapper {
     a: String,
 }
@@ -56,3 +57,482 @@ fn test_encode_foo_wrapper() {
     let decoded = decode(&expected).expect("decode failure");
     assert_eq!(foo, decoded);
 }
+
+#[derive(Debug, PartialEq, RlpEncodable, RlpDecodable, RlpSchema)]
+struct Bar {
+    id: u64,
+    foo: Foo,
+    wrappers: Vec<FooWrapper>,
+    parent: Option<FooWrapper>,
+}
+
+#[test]
+fn test_schema() {
+    assert_eq!(Foo::rlp_schema().to_string(), "[string]");
+    assert_eq!(FooWrapper::rlp_schema(), Schema::String);
+    assert_eq!(Bar::rlp_schema().to_string(), "[uint64, [string], list of string, optional string]");
+
+    let bar = Bar {
+        id: 1,
+        foo: Foo {
+            a: "cat".into(),
+        },
+        wrappers: vec![FooWrapper {
+            a: "dog".into(),
+        }],
+        parent: None,
+    };
+    assert_eq!(Bar::rlp_schema().validate(&Rlp::new(&encode(&bar))), Ok(()));
+    assert!(Bar::rlp_schema().validate(&Rlp::new(&encode(&bar.foo))).is_err());
+}
+
+#[derive(Debug, PartialEq, RlpEncodable, RlpDecodable)]
+struct Receipt {
+    status: u8,
+    #[rlp(option = "empty_data")]
+    contract: Option<String>,
+    #[rlp(option = "trailing")]
+    gas_used: Option<u64>,
+    #[rlp(option = "trailing")]
+    logs: Option<Vec<u8>>,
+}
+
+#[test]
+fn test_option_as_empty_data() {
+    let receipt = Receipt {
+        status: 1,
+        contract: None,
+        gas_used: Some(2),
+        logs: Some(vec![3]),
+    };
+    let expected = vec![0xc4, 0x01, 0x80, 0x02, 0x03];
+    assert_eq!(encode(&receipt), expected);
+    assert_eq!(decode(&expected), Ok(receipt));
+
+    let receipt = Receipt {
+        status: 1,
+        contract: Some("cat".into()),
+        gas_used: Some(2),
+        logs: Some(vec![3]),
+    };
+    let expected = vec![0xc7, 0x01, 0x83, b'c', b'a', b't', 0x02, 0x03];
+    assert_eq!(encode(&receipt), expected);
+    assert_eq!(decode(&expected), Ok(receipt));
+}
+
+#[test]
+fn test_option_as_trailing() {
+    let receipt = Receipt {
+        status: 1,
+        contract: None,
+        gas_used: Some(2),
+        logs: None,
+    };
+    let expected = vec![0xc3, 0x01, 0x80, 0x02];
+    assert_eq!(encode(&receipt), expected);
+    assert_eq!(decode(&expected), Ok(receipt));
+
+    let receipt = Receipt {
+        status: 1,
+        contract: None,
+        gas_used: None,
+        logs: None,
+    };
+    let expected = vec![0xc2, 0x01, 0x80];
+    assert_eq!(encode(&receipt), expected);
+    assert_eq!(decode(&expected), Ok(receipt));
+}
+
+#[test]
+#[should_panic(expected = "a trailing None cannot be followed by Some")]
+fn test_option_as_trailing_with_gap() {
+    encode(&Receipt {
+        status: 1,
+        contract: None,
+        gas_used: None,
+        logs: Some(vec![3]),
+    });
+}
+
+#[derive(Debug, PartialEq, RlpEncodable, RlpDecodable)]
+enum Message {
+    Ping,
+    Hello(u8, String),
+    #[rlp(tag = 7)]
+    Status {
+        version: u8,
+        #[rlp(option = "trailing")]
+        head: Option<u64>,
+    },
+    Peers(Vec<FooWrapper>),
+}
+
+#[derive(Debug, PartialEq, RlpEncodable, RlpDecodable)]
+enum Direction {
+    Inbound,
+    #[rlp(tag = 5)]
+    Outbound,
+    Unknown,
+}
+
+#[test]
+fn test_enum_as_tagged_list() {
+    let cases = vec![
+        (Message::Ping, vec![0xc1, 0x80]),
+        (Message::Hello(2, "cat".into()), vec![0xc6, 0x01, 0x02, 0x83, b'c', b'a', b't']),
+        (
+            Message::Status {
+                version: 1,
+                head: Some(9),
+            },
+            vec![0xc3, 0x07, 0x01, 0x09],
+        ),
+        (
+            Message::Status {
+                version: 1,
+                head: None,
+            },
+            vec![0xc2, 0x07, 0x01],
+        ),
+        (
+            Message::Peers(vec![FooWrapper {
+                a: "dog".into(),
+            }]),
+            vec![0xc6, 0x08, 0xc4, 0x83, b'd', b'o', b'g'],
+        ),
+    ];
+    for (message, expected) in cases {
+        assert_eq!(encode(&message), expected);
+        assert_eq!(decode(&expected), Ok(message));
+    }
+}
+
+#[test]
+fn test_unit_enum_as_integer() {
+    let cases = vec![
+        (Direction::Inbound, vec![0x80]),
+        (Direction::Outbound, vec![0x05]),
+        (Direction::Unknown, vec![0x06]),
+    ];
+    for (direction, expected) in cases {
+        assert_eq!(encode(&direction), expected);
+        assert_eq!(decode(&expected), Ok(direction));
+    }
+    assert_eq!(encode_list::<Direction, _>(&[Direction::Outbound, Direction::Inbound]), vec![0xc2, 0x05, 0x80]);
+}
+
+#[test]
+fn test_unknown_tag() {
+    let err = decode::<Direction>(&[0x01]).unwrap_err();
+    assert_eq!(err, DecoderError::UnknownTag {
+        type_name: "Direction",
+        tag: 1,
+    });
+    assert_eq!(err.to_string(), "RLP tag 1 is not a variant of Direction");
+    assert_eq!(decode::<Message>(&[0xc1, 0x03]), Err(DecoderError::UnknownTag {
+        type_name: "Message",
+        tag: 3,
+    }));
+}
+
+fn unknown_name() -> String {
+    "unknown".into()
+}
+
+#[derive(Debug, PartialEq, RlpEncodable, RlpDecodable, RlpSchema)]
+struct Block {
+    number: u64,
+    #[rlp(skip)]
+    hash_cache: Option<[u8; 4]>,
+    #[rlp(skip, default = "unknown_name()")]
+    author: String,
+    parent: u64,
+}
+
+#[test]
+fn test_skip() {
+    let block = Block {
+        number: 1,
+        hash_cache: Some([1, 2, 3, 4]),
+        author: "cat".into(),
+        parent: 2,
+    };
+    let expected = vec![0xc2, 0x01, 0x02];
+    assert_eq!(encode(&block), expected);
+    assert_eq!(decode(&expected), Ok(Block {
+        number: 1,
+        hash_cache: None,
+        author: "unknown".into(),
+        parent: 2,
+    }));
+    assert_eq!(Block::rlp_schema().to_string(), "[uint64, uint64]");
+}
+
+#[derive(Debug, PartialEq, RlpEncodable, RlpDecodable)]
+enum Event {
+    Created {
+        id: u8,
+        #[rlp(skip, default = "7")]
+        seen: u8,
+        #[rlp(option = "trailing")]
+        name: Option<String>,
+        #[rlp(skip)]
+        cache: Vec<u8>,
+    },
+}
+
+#[test]
+fn test_skip_in_variant() {
+    let event = Event::Created {
+        id: 1,
+        seen: 3,
+        name: None,
+        cache: vec![1],
+    };
+    let expected = vec![0xc2, 0x80, 0x01];
+    assert_eq!(encode(&event), expected);
+    assert_eq!(decode(&expected), Ok(Event::Created {
+        id: 1,
+        seen: 7,
+        name: None,
+        cache: vec![],
+    }));
+}
+
+#[derive(Debug, PartialEq, RlpEncodable, RlpDecodable)]
+struct Handshake {
+    version: u8,
+    #[rlp(trailing)]
+    network: u64,
+    #[rlp(trailing)]
+    fork: Option<Vec<u8>>,
+}
+
+#[test]
+fn test_trailing() {
+    let handshake = Handshake {
+        version: 5,
+        network: 3,
+        fork: Some(vec![1, 2]),
+    };
+    let expected = vec![0xc5, 0x05, 0x03, 0x82, 0x01, 0x02];
+    assert_eq!(encode(&handshake), expected);
+    assert_eq!(decode(&expected), Ok(handshake));
+
+    let handshake = Handshake {
+        version: 5,
+        network: 3,
+        fork: None,
+    };
+    let expected = vec![0xc2, 0x05, 0x03];
+    assert_eq!(encode(&handshake), expected);
+    assert_eq!(decode(&expected), Ok(handshake));
+}
+
+#[test]
+fn test_trailing_from_older_peer() {
+    assert_eq!(decode(&[0xc1, 0x05]), Ok(Handshake {
+        version: 5,
+        network: 0,
+        fork: None,
+    }));
+    assert!(decode::<Handshake>(&[0xc0]).is_err());
+}
+
+#[derive(Debug, PartialEq, RlpEncodable, RlpDecodable, RlpSchema)]
+struct Envelope<T, U = u8>
+where
+    U: Clone, {
+    kind: U,
+    body: T,
+    extra: Vec<T>,
+}
+
+#[derive(Debug, PartialEq, RlpEncodableWrapper, RlpDecodableWrapper, RlpSchemaWrapper)]
+struct Wrapped<T>(T);
+
+#[derive(Debug, PartialEq, RlpEncodable)]
+struct Borrowed<'a> {
+    name: &'a str,
+    data: &'a [u8],
+}
+
+#[derive(Debug, PartialEq, RlpEncodable, RlpDecodable)]
+enum Either<L, R> {
+    Left(L),
+    Right(R),
+}
+
+trait Protocol {
+    type Message;
+}
+
+struct Eth;
+
+impl Protocol for Eth {
+    type Message = String;
+}
+
+#[derive(RlpEncodable, RlpDecodable)]
+#[rlp(bound = "P: Protocol, P::Message: rlp::Encodable + rlp::Decodable")]
+struct Packet<P: Protocol> {
+    id: u8,
+    message: P::Message,
+}
+
+#[test]
+fn test_generics() {
+    let envelope = Envelope {
+        kind: 1u8,
+        body: "cat".to_string(),
+        extra: vec!["dog".to_string()],
+    };
+    let expected = vec![0xca, 0x01, 0x83, b'c', b'a', b't', 0xc4, 0x83, b'd', b'o', b'g'];
+    assert_eq!(encode(&envelope), expected);
+    assert_eq!(decode(&expected), Ok(envelope));
+    assert_eq!(Envelope::<String>::rlp_schema().to_string(), "[uint8, string, list of string]");
+
+    assert_eq!(encode(&Wrapped(7u16)), vec![0x07]);
+    assert_eq!(decode(&[0x07]), Ok(Wrapped(7u16)));
+    assert_eq!(Wrapped::<u16>::rlp_schema(), Schema::Uint(2));
+
+    let borrowed = Borrowed {
+        name: "cat",
+        data: &[0x01, 0x02],
+    };
+    assert_eq!(encode(&borrowed), vec![0xc7, 0x83, b'c', b'a', b't', 0x82, 0x01, 0x02]);
+
+    let either: Either<u8, String> = Either::Right("cat".into());
+    assert_eq!(decode(&encode(&either)), Ok(either));
+}
+
+#[test]
+fn test_bound() {
+    let packet = Packet::<Eth> {
+        id: 1,
+        message: "cat".into(),
+    };
+    let decoded: Packet<Eth> = decode(&encode(&packet)).unwrap();
+    assert_eq!(decoded.id, 1);
+    assert_eq!(decoded.message, "cat");
+}
+
+/// Encodes `u32` as exactly 4 big-endian bytes.
+mod fixed_u32 {
+    use rlp::{DecoderError, Rlp, RlpStream};
+
+    pub fn append(value: &u32, stream: &mut RlpStream) {
+        stream.append(&value.to_be_bytes().to_vec());
+    }
+
+    pub fn decode(rlp: &Rlp) -> Result<u32, DecoderError> {
+        let bytes = rlp.data()?;
+        if bytes.len() != 4 {
+            return Err(DecoderError::custom("expected 4 bytes"))
+        }
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+}
+
+fn append_duration(value: &Duration, stream: &mut RlpStream) {
+    stream.append(&value.as_secs());
+}
+
+fn decode_duration(rlp: &Rlp) -> Result<Duration, DecoderError> {
+    rlp.as_val().map(Duration::from_secs)
+}
+
+#[derive(Debug, PartialEq, RlpEncodable, RlpDecodable)]
+struct Lease {
+    #[rlp(with = "fixed_u32")]
+    id: u32,
+    #[rlp(append_with = "append_duration", decode_with = "decode_duration")]
+    ttl: Duration,
+    #[rlp(with = "fixed_u32", trailing)]
+    renewals: u32,
+}
+
+#[derive(RlpEncodable, RlpSchema)]
+struct LeaseTerms {
+    #[rlp(with = "fixed_u32")]
+    id: u32,
+    #[rlp(append_with = "append_duration", decode_with = "decode_duration")]
+    ttl: Duration,
+}
+
+#[derive(Debug, PartialEq, RlpEncodable, RlpDecodable)]
+struct Memo {
+    #[rlp(with = "rlp::byte_list")]
+    text: Vec<u8>,
+}
+
+#[test]
+fn test_byte_list_keeps_list_form() {
+    let memo = Memo {
+        text: vec![1, 2],
+    };
+    let expected = vec![0xc3, 0xc2, 0x01, 0x02];
+    assert_eq!(encode(&memo), expected);
+    assert_eq!(decode(&expected), Ok(memo));
+}
+
+#[test]
+fn test_with() {
+    let lease = Lease {
+        id: 1,
+        ttl: Duration::from_secs(60),
+        renewals: 2,
+    };
+    let expected = vec![0xcb, 0x84, 0x00, 0x00, 0x00, 0x01, 0x3c, 0x84, 0x00, 0x00, 0x00, 0x02];
+    assert_eq!(encode(&lease), expected);
+    assert_eq!(decode(&expected), Ok(lease));
+    assert_eq!(decode(&[0xc6, 0x84, 0x00, 0x00, 0x00, 0x01, 0x3c]), Ok(Lease {
+        id: 1,
+        ttl: Duration::from_secs(60),
+        renewals: 0,
+    }));
+    assert_eq!(decode::<Lease>(&[0xc3, 0x01, 0x3c, 0x02]), Err(DecoderError::Custom("expected 4 bytes".into())));
+    let terms = LeaseTerms {
+        id: 1,
+        ttl: Duration::from_secs(60),
+    };
+    assert_eq!(LeaseTerms::rlp_schema().to_string(), "[any, any]");
+    assert_eq!(LeaseTerms::rlp_schema().validate(&Rlp::new(&encode(&terms))), Ok(()));
+}
+
+mod chain {
+    use rlp_derive::{RlpDecodable, RlpEncodable, RlpSchema};
+
+    #[derive(Debug, PartialEq, RlpEncodable, RlpDecodable, RlpSchema)]
+    pub struct Header {
+        pub number: u64,
+    }
+}
+
+type Hashes = Vec<[u8; 32]>;
+
+#[derive(Debug, PartialEq, RlpEncodable, RlpDecodable, RlpSchema)]
+struct Chain {
+    headers: std::vec::Vec<chain::Header>,
+    hashes: Hashes,
+    uncles: Vec<Vec<u64>>,
+    extra: Vec<u8>,
+}
+
+#[test]
+fn test_nested_containers() {
+    let chain = Chain {
+        headers: vec![chain::Header {
+            number: 1,
+        }],
+        hashes: vec![[0xab; 32]],
+        uncles: vec![vec![1, 2], vec![]],
+        extra: vec![0xff],
+    };
+    let mut expected = vec![0xec, 0xc2, 0xc1, 0x01, 0xe1, 0xa0];
+    expected.extend_from_slice(&[0xab; 32]);
+    expected.extend_from_slice(&[0xc4, 0xc2, 0x01, 0x02, 0xc0, 0x81, 0xff]);
+    assert_eq!(encode(&chain), expected);
+    assert_eq!(decode(&expected), Ok(chain));
+    assert_eq!(Chain::rlp_schema().to_string(), "[list of [uint64], list of bytes32, list of list of uint64, bytes]");
+}
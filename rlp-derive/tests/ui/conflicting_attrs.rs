@@ -0,0 +1,29 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use rlp_derive::{RlpDecodable, RlpEncodable};
+
+#[derive(RlpEncodable)]
+struct Block {
+    #[rlp(skip, trailing)]
+    cache: Vec<u8>,
+}
+
+#[derive(RlpDecodable)]
+struct Header {
+    #[rlp(default = "7")]
+    number: u64,
+}
+
+#[derive(RlpEncodable)]
+struct Lease {
+    #[rlp(with = "fixed_u32", option = "empty_data")]
+    id: Option<u32>,
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use rlp_derive::RlpEncodable;
+
+#[derive(RlpEncodable)]
+enum Message {
+    Ping,
+    #[rlp(tag = 0)]
+    Pong,
+}
+
+fn main() {}
@@ -0,0 +1,33 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use rlp_derive::{RlpDecodable, RlpEncodable};
+
+#[derive(RlpEncodable)]
+struct Receipt {
+    #[rlp(option = "empty_list")]
+    logs: Option<Vec<u8>>,
+}
+
+#[derive(RlpDecodable)]
+struct Lease {
+    #[rlp(with = 32)]
+    id: u32,
+}
+
+#[derive(RlpEncodable)]
+#[rlp(bound = "T Encodable")]
+struct Wrapped<T>(T);
+
+#[derive(RlpEncodable)]
+enum Direction {
+    #[rlp(tag = "up")]
+    Up,
+}
+
+fn main() {}
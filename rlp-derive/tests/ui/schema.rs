@@ -0,0 +1,36 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use rlp_derive::RlpSchema;
+
+#[derive(RlpSchema)]
+enum Direction {
+    Up,
+}
+
+#[derive(RlpSchema)]
+struct Receipt {
+    #[rlp(option = "empty_data")]
+    logs: Option<Vec<u8>>,
+}
+
+#[derive(RlpSchema)]
+struct Handshake {
+    version: u8,
+    #[rlp(trailing)]
+    fork: Option<Vec<u8>>,
+}
+
+#[derive(RlpSchema)]
+struct Status {
+    version: u8,
+    #[rlp(trailing)]
+    height: u64,
+}
+
+fn main() {}
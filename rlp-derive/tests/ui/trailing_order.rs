@@ -0,0 +1,27 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use rlp_derive::{RlpDecodable, RlpEncodable};
+
+#[derive(RlpDecodable)]
+struct Handshake {
+    #[rlp(trailing)]
+    fork: Option<Vec<u8>>,
+    version: u8,
+}
+
+#[derive(RlpEncodable)]
+struct Mixed {
+    a: u8,
+    #[rlp(trailing)]
+    b: Option<u8>,
+    #[rlp(trailing)]
+    c: u8,
+}
+
+fn main() {}
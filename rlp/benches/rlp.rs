@@ -116,3 +116,46 @@ fn bench_decode_1000_values(b: &mut Bencher) {
         }
     });
 }
+
+#[bench]
+fn bench_decode_1000_values_iter(b: &mut Bencher) {
+    let mut stream = RlpStream::new_list(1000);
+    for _ in 0..1000 {
+        stream.append(&U256::from(1));
+    }
+    let data = stream.out();
+    b.iter(|| {
+        let rlp = Rlp::new(&data);
+        for item in rlp.iter() {
+            let _: U256 = item.as_val().unwrap();
+        }
+    });
+}
+
+#[bench]
+fn bench_decode_1000_values_iter_rev(b: &mut Bencher) {
+    let mut stream = RlpStream::new_list(1000);
+    for _ in 0..1000 {
+        stream.append(&U256::from(1));
+    }
+    let data = stream.out();
+    b.iter(|| {
+        let rlp = Rlp::new(&data);
+        for item in rlp.iter().rev() {
+            let _: U256 = item.as_val().unwrap();
+        }
+    });
+}
+
+#[bench]
+fn bench_decode_1000_values_as_list(b: &mut Bencher) {
+    let mut stream = RlpStream::new_list(1000);
+    for _ in 0..1000 {
+        stream.append(&U256::from(1));
+    }
+    let data = stream.out();
+    b.iter(|| {
+        let rlp = Rlp::new(&data);
+        let _: Vec<U256> = rlp.as_list().unwrap();
+    });
+}
@@ -0,0 +1,36 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Bytes encoded as a list of integers instead of data.
+//!
+//! Before 0.6, `#[derive(RlpEncodable, RlpDecodable)]` encoded a `Vec<u8>` field this way.
+//! Use the module with `#[rlp(with = "rlp::byte_list")]` to keep reading and writing that form.
+//!
+//! ```rust
+//! use rlp::{Rlp, RlpStream};
+//!
+//! fn main () {
+//!     let mut stream = RlpStream::new();
+//!     rlp::byte_list::append(&[1, 2], &mut stream);
+//!     let out = stream.out();
+//!     assert_eq!(out, vec![0xc2, 0x01, 0x02]);
+//!     assert_eq!(rlp::byte_list::decode(&Rlp::new(&out)), Ok(vec![1, 2]));
+//! }
+//! ```
+
+use super::{DecoderError, Rlp, RlpStream};
+
+/// Appends the bytes as a list of integers.
+pub fn append(value: &[u8], stream: &mut RlpStream) {
+    stream.append_list::<u8, _>(value);
+}
+
+/// Decodes a list of integers as bytes.
+pub fn decode(rlp: &Rlp) -> Result<Vec<u8>, DecoderError> {
+    rlp.as_list()
+}
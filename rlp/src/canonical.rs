@@ -0,0 +1,76 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::{DecoderError, PayloadInfo, RlpStream};
+use std::fmt;
+
+/// A violation of the canonical form accepted by `PayloadInfo::from_lenient`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CanonicalFixKind {
+    /// A single byte less than 0x80 was encoded as a string, e.g. `0x81 0x05`.
+    SingleByteIndirection,
+    /// A payload of 55 bytes or less used the long form header.
+    LongFormHeader,
+    /// The length of the payload had leading zero bytes.
+    LengthWithZeroPrefix,
+}
+
+impl fmt::Display for CanonicalFixKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CanonicalFixKind::SingleByteIndirection => write!(f, "single byte indirection"),
+            CanonicalFixKind::LongFormHeader => write!(f, "long form header"),
+            CanonicalFixKind::LengthWithZeroPrefix => write!(f, "length with zero prefix"),
+        }
+    }
+}
+
+/// A fix made by `canonicalize`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CanonicalFix {
+    /// Indexes of the fixed item from the root.
+    pub path: Vec<usize>,
+    pub kind: CanonicalFixKind,
+}
+
+impl fmt::Display for CanonicalFix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}: {}", self.path, self.kind)
+    }
+}
+
+/// Re-encodes the first item of `bytes` into the stream and returns the number of bytes consumed.
+pub(crate) fn canonicalize_item(
+    stream: &mut RlpStream,
+    path: &mut Vec<usize>,
+    bytes: &[u8],
+    fixes: &mut Vec<CanonicalFix>,
+) -> Result<usize, DecoderError> {
+    let (info, kinds) = PayloadInfo::from_lenient(bytes)?;
+    fixes.extend(kinds.into_iter().map(|kind| CanonicalFix {
+        path: path.clone(),
+        kind,
+    }));
+
+    let payload = &bytes[info.header_len..info.total()];
+    if bytes[0] >= 0xc0 {
+        stream.begin_unbounded_list();
+        let mut offset = 0;
+        let mut index = 0;
+        while offset < payload.len() {
+            path.push(index);
+            offset += canonicalize_item(stream, path, &payload[offset..], fixes)?;
+            path.pop();
+            index += 1;
+        }
+        stream.complete_unbounded_list();
+    } else {
+        stream.append(&payload);
+    }
+    Ok(info.total())
+}
@@ -0,0 +1,168 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::{DecoderError, Encodable, PayloadInfo};
+use bytes::BytesMut;
+use std::error::Error as StdError;
+use std::{fmt, io};
+use tokio_util::codec::{Decoder, Encoder};
+
+/// The default limit of a frame, 16 MiB.
+pub const DEFAULT_MAX_FRAME_SIZE: usize = 16 * 1024 * 1024;
+
+/// Splits a byte stream into top-level rlp items and writes `Encodable` values into it.
+///
+/// Each decoded frame holds exactly one complete item, header included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RlpCodec {
+    max_frame_size: usize,
+}
+
+impl RlpCodec {
+    pub fn new() -> Self {
+        Self::with_max_frame_size(DEFAULT_MAX_FRAME_SIZE)
+    }
+
+    /// Creates a codec which rejects items longer than `max_frame_size` bytes including their headers.
+    pub fn with_max_frame_size(max_frame_size: usize) -> Self {
+        RlpCodec {
+            max_frame_size,
+        }
+    }
+
+    pub fn max_frame_size(&self) -> usize {
+        self.max_frame_size
+    }
+}
+
+impl Default for RlpCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Error concerning the rlp codec.
+#[derive(Debug)]
+pub enum CodecError {
+    /// The underlying stream failed.
+    Io(io::Error),
+    /// The header of an item is invalid.
+    Decoder(DecoderError),
+    /// An item is longer than the maximum frame size.
+    FrameTooBig {
+        max: usize,
+        got: usize,
+    },
+}
+
+impl StdError for CodecError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            CodecError::Io(err) => Some(err),
+            CodecError::Decoder(err) => Some(err),
+            CodecError::FrameTooBig {
+                ..
+            } => None,
+        }
+    }
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CodecError::Io(err) => write!(f, "io error: {}", err),
+            CodecError::Decoder(err) => write!(f, "invalid rlp header: {}", err),
+            CodecError::FrameTooBig {
+                max,
+                got,
+            } => write!(f, "frame of {} bytes exceeds the maximum of {} bytes", got, max),
+        }
+    }
+}
+
+impl From<io::Error> for CodecError {
+    fn from(err: io::Error) -> Self {
+        CodecError::Io(err)
+    }
+}
+
+impl From<DecoderError> for CodecError {
+    fn from(err: DecoderError) -> Self {
+        CodecError::Decoder(err)
+    }
+}
+
+impl Decoder for RlpCodec {
+    type Item = BytesMut;
+    type Error = CodecError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if src.is_empty() {
+            return Ok(None)
+        }
+        let header_len = PayloadInfo::header_len_of(src[0]);
+        if src.len() < header_len {
+            src.reserve(header_len - src.len());
+            return Ok(None)
+        }
+
+        let info = PayloadInfo::from(&src[..header_len])?;
+        let total = info.header_len.checked_add(info.value_len).ok_or(CodecError::FrameTooBig {
+            max: self.max_frame_size,
+            got: usize::MAX,
+        })?;
+        if total > self.max_frame_size {
+            return Err(CodecError::FrameTooBig {
+                max: self.max_frame_size,
+                got: total,
+            })
+        }
+        if src.len() < total {
+            src.reserve(total - src.len());
+            return Ok(None)
+        }
+        Ok(Some(src.split_to(total)))
+    }
+}
+
+impl<E> Encoder<E> for RlpCodec
+where
+    E: Encodable,
+{
+    type Error = CodecError;
+
+    fn encode(&mut self, item: E, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let bytes = item.rlp_bytes();
+        if bytes.len() > self.max_frame_size {
+            return Err(CodecError::FrameTooBig {
+                max: self.max_frame_size,
+                got: bytes.len(),
+            })
+        }
+        dst.extend_from_slice(&bytes);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_waits_for_the_whole_item() {
+        let mut codec = RlpCodec::new();
+        let encoded = crate::encode(&"a long string which needs the long form header, a long one");
+        let mut src = BytesMut::new();
+        for (i, byte) in encoded.iter().enumerate() {
+            assert_eq!(codec.decode(&mut src).unwrap(), None, "decoded with {} bytes", i);
+            src.extend_from_slice(&[*byte]);
+        }
+        assert_eq!(codec.decode(&mut src).unwrap().as_deref(), Some(&encoded[..]));
+        assert!(src.is_empty());
+    }
+}
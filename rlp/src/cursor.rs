@@ -0,0 +1,134 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::{Decodable, DecoderError, DecodingProfile, PayloadInfo, Rlp};
+
+/// Reads the items of a list one by one.
+///
+/// ```rust
+/// use rlp::*;
+///
+/// struct Animal {
+///     name: String,
+///     legs: u8,
+/// }
+///
+/// impl Decodable for Animal {
+///     fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+///         let mut cursor = rlp.cursor()?;
+///         let animal = Animal {
+///             name: cursor.next()?,
+///             legs: cursor.next()?,
+///         };
+///         cursor.finish()?;
+///         Ok(animal)
+///     }
+/// }
+///
+/// fn main () {
+///     let data = vec![0xc5, 0x83, b'c', b'a', b't', 0x04];
+///     let cat: Animal = decode(&data).unwrap();
+///     assert_eq!(cat.name, "cat");
+///     assert_eq!(cat.legs, 4);
+///
+///     let data = vec![0xc6, 0x83, b'c', b'a', b't', 0x04, 0x01];
+///     assert_eq!(decode::<Animal>(&data).err(), Some(DecoderError::RlpIncorrectListLen {
+///         expected: 2,
+///         got: 3,
+///     }));
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RlpCursor<'a> {
+    /// The payload not yet consumed.
+    payload: &'a [u8],
+    /// The number of consumed items.
+    consumed: usize,
+    profile: DecodingProfile,
+}
+
+impl<'a> RlpCursor<'a> {
+    pub fn new(rlp: &Rlp<'a>) -> Result<Self, DecoderError> {
+        if !rlp.is_list() {
+            return Err(DecoderError::RlpExpectedToBeList)
+        }
+        let info = rlp.payload_info()?;
+        Ok(RlpCursor {
+            payload: &rlp.as_raw()[info.header_len..info.total()],
+            consumed: 0,
+            profile: rlp.profile(),
+        })
+    }
+
+    /// Decodes the next item.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next<T>(&mut self) -> Result<T, DecoderError>
+    where
+        T: Decodable, {
+        self.next_rlp()?.as_val()
+    }
+
+    /// Decodes the next item as a list.
+    pub fn next_list<T>(&mut self) -> Result<Vec<T>, DecoderError>
+    where
+        T: Decodable, {
+        self.next_rlp()?.as_list()
+    }
+
+    /// Returns the next item without consuming it.
+    pub fn peek(&self) -> Option<Rlp<'a>> {
+        self.first().ok()
+    }
+
+    /// The number of items not yet consumed.
+    pub fn remaining(&self) -> usize {
+        let mut rest = self.clone();
+        let mut count = 0;
+        while rest.next_rlp().is_ok() {
+            count += 1;
+        }
+        count
+    }
+
+    /// Fails unless every item has been consumed.
+    pub fn finish(self) -> Result<(), DecoderError> {
+        if self.payload.is_empty() {
+            return Ok(())
+        }
+        // A malformed item is reported as it is.
+        self.first()?;
+        Err(DecoderError::RlpIncorrectListLen {
+            expected: self.consumed,
+            got: self.consumed + self.remaining(),
+        })
+    }
+
+    fn first(&self) -> Result<Rlp<'a>, DecoderError> {
+        if self.payload.is_empty() {
+            return Err(DecoderError::RlpIncorrectListLen {
+                expected: self.consumed + 1,
+                got: self.consumed,
+            })
+        }
+        let info = PayloadInfo::from(self.payload)?;
+        match info.header_len.checked_add(info.value_len) {
+            Some(total) if total <= self.payload.len() => Ok(Rlp::with_profile(&self.payload[..total], self.profile)),
+            total => Err(DecoderError::RlpIsTooShort {
+                expected: total.unwrap_or(usize::MAX),
+                got: self.payload.len(),
+            }),
+        }
+    }
+
+    fn next_rlp(&mut self) -> Result<Rlp<'a>, DecoderError> {
+        let item = self.first()?;
+        self.payload = &self.payload[item.as_raw().len()..];
+        self.consumed += 1;
+        Ok(item)
+    }
+}
@@ -0,0 +1,112 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::{DecoderError, Rlp};
+use std::{cmp, fmt};
+
+/// The way two items at the same path differ.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DifferenceKind {
+    /// Both items are data, but with different contents.
+    ChangedData,
+    /// Both items are lists, but with different number of items.
+    LengthMismatch,
+    /// One item is a list and the other is data.
+    TypeMismatch,
+}
+
+impl fmt::Display for DifferenceKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DifferenceKind::ChangedData => write!(f, "changed data"),
+            DifferenceKind::LengthMismatch => write!(f, "length mismatch"),
+            DifferenceKind::TypeMismatch => write!(f, "type mismatch"),
+        }
+    }
+}
+
+/// A single difference found by `diff`.
+#[derive(Debug, Clone)]
+pub struct Difference<'a> {
+    /// Indexes of the item from the root.
+    pub path: Vec<usize>,
+    pub kind: DifferenceKind,
+    /// The item in the first RLP.
+    pub left: Rlp<'a>,
+    /// The item in the second RLP.
+    pub right: Rlp<'a>,
+}
+
+impl<'a> fmt::Display for Difference<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}: {}, {} != {}", self.path, self.kind, self.left, self.right)
+    }
+}
+
+impl<'a> Difference<'a> {
+    fn new(path: &[usize], kind: DifferenceKind, left: &Rlp<'a>, right: &Rlp<'a>) -> Self {
+        Difference {
+            path: path.to_vec(),
+            kind,
+            left: left.clone(),
+            right: right.clone(),
+        }
+    }
+}
+
+pub(crate) fn diff_items<'a>(
+    path: &mut Vec<usize>,
+    left: &Rlp<'a>,
+    right: &Rlp<'a>,
+    differences: &mut Vec<Difference<'a>>,
+) -> Result<(), DecoderError> {
+    if left.as_raw() == right.as_raw() {
+        return Ok(())
+    }
+
+    match (left.is_list(), right.is_list()) {
+        (true, true) => {
+            let left_count = left.strict_item_count()?;
+            let right_count = right.strict_item_count()?;
+            if left_count != right_count {
+                check_item(left)?;
+                check_item(right)?;
+                differences.push(Difference::new(path, DifferenceKind::LengthMismatch, left, right));
+            }
+            // items at the same index are still compared when the lengths differ
+            for index in 0..cmp::min(left_count, right_count) {
+                path.push(index);
+                diff_items(path, &left.at(index)?, &right.at(index)?, differences)?;
+                path.pop();
+            }
+        }
+        (false, false) => {
+            if left.data()? != right.data()? {
+                differences.push(Difference::new(path, DifferenceKind::ChangedData, left, right));
+            }
+        }
+        _ => {
+            check_item(left)?;
+            check_item(right)?;
+            differences.push(Difference::new(path, DifferenceKind::TypeMismatch, left, right));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the whole item is well-formed, so that the difference holding it can be displayed.
+fn check_item(rlp: &Rlp<'_>) -> Result<(), DecoderError> {
+    if !rlp.is_list() {
+        rlp.data()?;
+        return Ok(())
+    }
+    for index in 0..rlp.strict_item_count()? {
+        check_item(&rlp.at(index)?)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,182 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::{DecoderError, Encodable, Rlp, RlpStream};
+
+#[derive(Debug)]
+enum Edit {
+    Replace(Vec<u8>),
+    Insert(Vec<u8>),
+    Remove,
+    Append(Vec<u8>),
+}
+
+/// Patches items of an encoded RLP without decoding the whole structure.
+///
+/// Every path is a list of indexes from the root, and always refers to the items of the original RLP.
+/// Only the headers of the lists enclosing an edited item are re-encoded; other items are copied verbatim.
+///
+/// ```rust
+/// use rlp::*;
+///
+/// fn main () {
+///     let data = vec![0xc9, 0x83, b'c', b'a', b't', 0xc4, 0x83, b'd', b'o', b'g'];
+///     let mut editor = RlpEditor::new(Rlp::new(&data));
+///     editor.replace(&[1, 0], &"cow").append(&[1], &"pig").remove(&[0]);
+///     let out = editor.out().unwrap();
+///     assert_eq!(out, vec![0xc9, 0xc8, 0x83, b'c', b'o', b'w', 0x83, b'p', b'i', b'g']);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct RlpEditor<'a> {
+    rlp: Rlp<'a>,
+    edits: Vec<(Vec<usize>, Edit)>,
+}
+
+impl<'a> RlpEditor<'a> {
+    pub fn new(rlp: Rlp<'a>) -> Self {
+        RlpEditor {
+            rlp,
+            edits: Vec::new(),
+        }
+    }
+
+    /// Replaces the item at `path` with the given value, chainable.
+    pub fn replace<E>(&mut self, path: &[usize], value: &E) -> &mut Self
+    where
+        E: Encodable, {
+        self.replace_raw(path, &value.rlp_bytes())
+    }
+
+    /// Replaces the item at `path` with raw (pre-serialised) RLP data, chainable.
+    pub fn replace_raw(&mut self, path: &[usize], bytes: &[u8]) -> &mut Self {
+        self.edits.push((path.to_vec(), Edit::Replace(bytes.to_vec())));
+        self
+    }
+
+    /// Inserts the given value in front of the item at `path`, chainable.
+    ///
+    /// The last index of `path` may be the length of the list to insert at its end.
+    pub fn insert<E>(&mut self, path: &[usize], value: &E) -> &mut Self
+    where
+        E: Encodable, {
+        self.insert_raw(path, &value.rlp_bytes())
+    }
+
+    /// Inserts raw (pre-serialised) RLP data in front of the item at `path`, chainable.
+    pub fn insert_raw(&mut self, path: &[usize], bytes: &[u8]) -> &mut Self {
+        self.edits.push((path.to_vec(), Edit::Insert(bytes.to_vec())));
+        self
+    }
+
+    /// Removes the item at `path`, chainable.
+    pub fn remove(&mut self, path: &[usize]) -> &mut Self {
+        self.edits.push((path.to_vec(), Edit::Remove));
+        self
+    }
+
+    /// Appends the given value to the end of the list at `path`, chainable.
+    pub fn append<E>(&mut self, path: &[usize], value: &E) -> &mut Self
+    where
+        E: Encodable, {
+        self.append_raw(path, &value.rlp_bytes())
+    }
+
+    /// Appends raw (pre-serialised) RLP data to the end of the list at `path`, chainable.
+    pub fn append_raw(&mut self, path: &[usize], bytes: &[u8]) -> &mut Self {
+        self.edits.push((path.to_vec(), Edit::Append(bytes.to_vec())));
+        self
+    }
+
+    /// Applies all edits and returns the new RLP.
+    pub fn out(&self) -> Result<Vec<u8>, DecoderError> {
+        let edits: Vec<_> = self.edits.iter().map(|(path, edit)| (&path[..], edit)).collect();
+        let mut stream = RlpStream::new();
+        write_item(&mut stream, &self.rlp, &edits)?;
+        Ok(stream.out())
+    }
+}
+
+fn conflict() -> DecoderError {
+    DecoderError::custom("Conflicting edits on the same item")
+}
+
+fn write_item(stream: &mut RlpStream, rlp: &Rlp<'_>, edits: &[(&[usize], &Edit)]) -> Result<(), DecoderError> {
+    if edits.is_empty() {
+        stream.append_raw(rlp.as_raw(), 1);
+        return Ok(())
+    }
+
+    let mut appended = Vec::new();
+    let mut children = Vec::new();
+    for &(path, edit) in edits {
+        match (path.is_empty(), edit) {
+            (true, Edit::Replace(bytes)) => {
+                if edits.len() != 1 {
+                    return Err(conflict())
+                }
+                stream.append_raw(bytes, 1);
+                return Ok(())
+            }
+            (true, Edit::Append(bytes)) => appended.push(bytes),
+            (true, _) => return Err(DecoderError::custom("The root item cannot be inserted or removed")),
+            (false, _) => children.push((path, edit)),
+        }
+    }
+
+    let item_count = rlp.strict_item_count()?;
+    for &(path, edit) in &children {
+        let is_insert_at_end = match edit {
+            Edit::Insert(_) => path.len() == 1,
+            _ => false,
+        };
+        if path[0] > item_count || (path[0] == item_count && !is_insert_at_end) {
+            return Err(DecoderError::RlpIsTooShort {
+                expected: path[0] + 1,
+                got: item_count,
+            })
+        }
+    }
+
+    stream.begin_unbounded_list();
+    for index in 0..=item_count {
+        let mut target = None;
+        let mut nested = Vec::new();
+        for &(path, edit) in children.iter().filter(|(path, _)| path[0] == index) {
+            match edit {
+                Edit::Insert(bytes) if path.len() == 1 => {
+                    stream.append_raw(bytes, 1);
+                }
+                Edit::Replace(_) | Edit::Remove if path.len() == 1 => {
+                    if target.is_some() {
+                        return Err(conflict())
+                    }
+                    target = Some(edit);
+                }
+                _ => nested.push((&path[1..], edit)),
+            }
+        }
+        if index == item_count {
+            break
+        }
+
+        match target {
+            Some(Edit::Replace(bytes)) if nested.is_empty() => {
+                stream.append_raw(bytes, 1);
+            }
+            Some(Edit::Remove) if nested.is_empty() => {}
+            Some(_) => return Err(conflict()),
+            None => write_item(stream, &rlp.at(index)?, &nested)?,
+        }
+    }
+    for bytes in appended {
+        stream.append_raw(bytes, 1);
+    }
+    stream.complete_unbounded_list();
+    Ok(())
+}
@@ -43,25 +43,163 @@ pub enum DecoderError {
         max: usize,
         index: usize,
     },
-    /// Declared length is invalid and results in overflow
     /// A string MUST NOT be null terminated.
     RlpNullTerminatedString,
+    /// Declared length is invalid and results in overflow
     RlpInvalidLength {
         expected: usize,
         got: usize,
     },
+    /// String is not valid UTF-8.
+    InvalidUtf8 {
+        valid_up_to: usize,
+    },
+    /// Integer has more bytes than its type can hold.
+    IntegerOverflow {
+        type_name: &'static str,
+        len: usize,
+    },
+    /// Integer has a leading zero byte, which is non-canonical.
+    LeadingZeroInteger,
+    /// Boolean is a single byte other than 0 and 1.
+    InvalidBool {
+        byte: u8,
+    },
+    /// Tag does not match any variant of the enum.
+    UnknownTag {
+        type_name: &'static str,
+        tag: u64,
+    },
     /// Custom rlp decoding error.
-    Custom(&'static str),
+    Custom(String),
+    /// An error with a description of what was being decoded.
+    Context {
+        context: String,
+        error: Box<DecoderError>,
+    },
+}
+
+impl DecoderError {
+    /// Creates a custom error from any message.
+    ///
+    /// ```rust
+    /// fn main () {
+    ///     let nonce = 3;
+    ///     let err = rlp::DecoderError::custom(format_args!("Unexpected nonce {}", nonce));
+    ///     assert_eq!(err.to_string(), "Unexpected nonce 3");
+    /// }
+    /// ```
+    pub fn custom<T>(message: T) -> Self
+    where
+        T: fmt::Display, {
+        DecoderError::Custom(message.to_string())
+    }
+
+    /// Wraps the error with a description of what was being decoded.
+    ///
+    /// ```rust
+    /// fn main () {
+    ///     let err = rlp::DecoderError::RlpExpectedToBeData.with_context("gas_limit").with_context("header");
+    ///     assert_eq!(err.to_string(), "header: gas_limit: RLP is expected to be data");
+    /// }
+    /// ```
+    pub fn with_context<C>(self, context: C) -> Self
+    where
+        C: Into<String>, {
+        DecoderError::Context {
+            context: context.into(),
+            error: Box::new(self),
+        }
+    }
+
+    /// Returns the innermost error, skipping every context.
+    pub fn root_cause(&self) -> &DecoderError {
+        match self {
+            DecoderError::Context {
+                error,
+                ..
+            } => error.root_cause(),
+            _ => self,
+        }
+    }
+}
+
+/// Adds `with_context` to the results of decoding.
+pub trait DecoderResultExt<T> {
+    /// Wraps the error, if any, with a description of what was being decoded.
+    fn with_context<C>(self, context: C) -> Result<T, DecoderError>
+    where
+        C: Into<String>;
+}
+
+impl<T> DecoderResultExt<T> for Result<T, DecoderError> {
+    fn with_context<C>(self, context: C) -> Result<T, DecoderError>
+    where
+        C: Into<String>, {
+        self.map_err(|err| err.with_context(context))
+    }
 }
 
 impl StdError for DecoderError {
-    fn description(&self) -> &str {
-        "builder error"
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            DecoderError::Context {
+                error,
+                ..
+            } => Some(error.as_ref()),
+            _ => None,
+        }
     }
 }
 
 impl fmt::Display for DecoderError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        fmt::Debug::fmt(&self, f)
+        match self {
+            DecoderError::RlpIsTooBig {
+                expected,
+                got,
+            } => write!(f, "RLP is too big: expected {} bytes, got {}", expected, got),
+            DecoderError::RlpIsTooShort {
+                expected,
+                got,
+            } => write!(f, "RLP is too short: expected {} bytes, got {}", expected, got),
+            DecoderError::RlpExpectedToBeList => write!(f, "RLP is expected to be a list"),
+            DecoderError::RlpExpectedToBeData => write!(f, "RLP is expected to be data"),
+            DecoderError::RlpIncorrectListLen {
+                expected,
+                got,
+            } => write!(f, "RLP list has an incorrect length: expected {} items, got {}", expected, got),
+            DecoderError::RlpDataLenWithZeroPrefix => write!(f, "RLP data length has a leading zero byte"),
+            DecoderError::RlpListLenWithZeroPrefix => write!(f, "RLP list length has a leading zero byte"),
+            DecoderError::RlpInvalidIndirection => write!(f, "RLP uses a longer representation than necessary"),
+            DecoderError::RlpInconsistentLengthAndData {
+                max,
+                index,
+            } => write!(f, "RLP needs {} bytes as declared, but only {} bytes exist", index, max),
+            DecoderError::RlpNullTerminatedString => write!(f, "RLP string is null terminated"),
+            DecoderError::RlpInvalidLength {
+                ..
+            } => write!(f, "RLP declares a length that overflows"),
+            DecoderError::InvalidUtf8 {
+                valid_up_to,
+            } => write!(f, "RLP string is not valid UTF-8 after {} bytes", valid_up_to),
+            DecoderError::IntegerOverflow {
+                type_name,
+                len,
+            } => write!(f, "RLP integer of {} bytes overflows {}", len, type_name),
+            DecoderError::LeadingZeroInteger => write!(f, "RLP integer has a leading zero byte"),
+            DecoderError::InvalidBool {
+                byte,
+            } => write!(f, "RLP bool must be 0 or 1, got {}", byte),
+            DecoderError::UnknownTag {
+                type_name,
+                tag,
+            } => write!(f, "RLP tag {} is not a variant of {}", tag, type_name),
+            DecoderError::Custom(message) => write!(f, "{}", message),
+            DecoderError::Context {
+                context,
+                error,
+            } => write!(f, "{}: {}", context, error),
+        }
     }
 }
@@ -9,8 +9,9 @@
 
 use super::stream::RlpStream;
 use super::traits::{Decodable, Encodable};
-use super::{DecoderError, Rlp};
+use super::{DecoderError, DecodingProfile, Rlp};
 use primitives::{H128, H160, H256, H512, H520, U256};
+use std::convert::TryFrom;
 use std::iter::{empty, once};
 use std::{cmp, mem, str};
 
@@ -35,6 +36,32 @@ pub fn decode_usize(bytes: &[u8]) -> Result<usize, DecoderError> {
     }
 }
 
+/// Checks the big-endian bytes of an integer fit in `size` bytes.
+///
+/// Leading zeros are stripped if the profile is `DecodingProfile::Legacy` and rejected otherwise.
+pub(crate) fn integer_bytes<'a>(
+    profile: DecodingProfile,
+    bytes: &'a [u8],
+    type_name: &'static str,
+    size: usize,
+) -> Result<&'a [u8], DecoderError> {
+    let zeros = bytes.iter().take_while(|&&byte| byte == 0).count();
+    let bytes = match profile {
+        DecodingProfile::Legacy => &bytes[zeros..],
+        DecodingProfile::Strict | DecodingProfile::Ethereum => bytes,
+    };
+    if bytes.len() > size {
+        return Err(DecoderError::IntegerOverflow {
+            type_name,
+            len: bytes.len(),
+        })
+    }
+    if zeros != 0 && profile != DecodingProfile::Legacy {
+        return Err(DecoderError::LeadingZeroInteger)
+    }
+    Ok(bytes)
+}
+
 impl Encodable for bool {
     fn rlp_append(&self, s: &mut RlpStream) {
         s.encoder().encode_iter(once(if *self {
@@ -47,47 +74,51 @@ impl Encodable for bool {
 
 impl Decodable for bool {
     fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
-        rlp.decoder().decode_value(|bytes| match bytes.len() {
-            0 => Ok(false),
-            1 => Ok(bytes[0] != 0),
-            got => Err(DecoderError::RlpIsTooBig {
+        let profile = rlp.profile();
+        rlp.decoder().decode_value(|bytes| match (profile, bytes) {
+            (DecodingProfile::Strict, [0]) | (DecodingProfile::Ethereum, []) | (DecodingProfile::Legacy, []) => Ok(false),
+            (_, [1]) => Ok(true),
+            (DecodingProfile::Legacy, [byte]) => Ok(*byte != 0),
+            (DecodingProfile::Strict, []) => Err(DecoderError::RlpIsTooShort {
                 expected: 1,
-                got,
+                got: 0,
+            }),
+            (_, [0]) => Err(DecoderError::LeadingZeroInteger),
+            (_, [byte]) => Err(DecoderError::InvalidBool {
+                byte: *byte,
+            }),
+            (_, bytes) => Err(DecoderError::IntegerOverflow {
+                type_name: "bool",
+                len: bytes.len(),
             }),
         })
     }
 }
 
-impl<'a> Encodable for &'a [u8] {
-    fn rlp_append(&self, s: &mut RlpStream) {
-        s.encoder().encode_value(self);
-    }
-}
-
-impl Encodable for Vec<u8> {
+impl<'a, T> Encodable for &'a [T]
+where
+    T: Encodable,
+{
     fn rlp_append(&self, s: &mut RlpStream) {
-        s.encoder().encode_value(self);
+        T::rlp_append_slice(self, s);
     }
 }
 
-impl Decodable for Vec<u8> {
-    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
-        rlp.decoder().decode_value(|bytes| Ok(bytes.to_vec()))
-    }
-}
-
-impl Encodable for Vec<Vec<u8>> {
+impl<T> Encodable for Vec<T>
+where
+    T: Encodable,
+{
     fn rlp_append(&self, s: &mut RlpStream) {
-        s.begin_list(self.len());
-        for e in self {
-            s.append(e);
-        }
+        T::rlp_append_slice(self, s);
     }
 }
 
-impl Decodable for Vec<Vec<u8>> {
+impl<T> Decodable for Vec<T>
+where
+    T: Decodable,
+{
     fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
-        rlp.as_list::<Vec<u8>>()
+        T::decode_vec(rlp)
     }
 }
 
@@ -133,20 +164,24 @@ impl Encodable for u8 {
             s.encoder().encode_iter(empty());
         }
     }
+
+    fn rlp_append_slice(values: &[Self], s: &mut RlpStream) {
+        s.encoder().encode_value(values);
+    }
 }
 
 impl Decodable for u8 {
     fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
-        rlp.decoder().decode_value(|bytes| match bytes.len() {
-            1 if bytes[0] != 0 => Ok(bytes[0]),
-            0 => Ok(0),
-            1 => Err(DecoderError::RlpInvalidIndirection),
-            got => Err(DecoderError::RlpIsTooBig {
-                expected: 1,
-                got,
-            }),
+        let profile = rlp.profile();
+        rlp.decoder().decode_value(|bytes| match integer_bytes(profile, bytes, "u8", 1)? {
+            [byte] => Ok(*byte),
+            _ => Ok(0),
         })
     }
+
+    fn decode_vec(rlp: &Rlp) -> Result<Vec<Self>, DecoderError> {
+        rlp.decoder().decode_value(|bytes| Ok(bytes.to_vec()))
+    }
 }
 
 macro_rules! impl_encodable_for_u {
@@ -165,23 +200,15 @@ macro_rules! impl_decodable_for_u {
     ($name: ident) => {
         impl Decodable for $name {
             fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
-                rlp.decoder().decode_value(|bytes| match bytes.len() {
-                    0 | 1 => u8::decode(rlp).map($name::from),
-                    l if l <= mem::size_of::<$name>() => {
-                        if bytes[0] == 0 {
-                            return Err(DecoderError::RlpInvalidIndirection)
-                        }
-                        let mut res = 0 as $name;
-                        for (i, byte) in bytes.iter().enumerate() {
-                            let shift = (l - 1 - i) * 8;
-                            res += $name::from(*byte) << shift;
-                        }
-                        Ok(res)
+                let profile = rlp.profile();
+                rlp.decoder().decode_value(|bytes| {
+                    let bytes = integer_bytes(profile, bytes, stringify!($name), mem::size_of::<$name>())?;
+                    let mut res = 0 as $name;
+                    for (i, byte) in bytes.iter().enumerate() {
+                        let shift = (bytes.len() - 1 - i) * 8;
+                        res += $name::from(*byte) << shift;
                     }
-                    got => Err(DecoderError::RlpIsTooBig {
-                        expected: mem::size_of::<$name>(),
-                        got,
-                    }),
+                    Ok(res)
                 })
             }
         }
@@ -206,7 +233,18 @@ impl Encodable for usize {
 
 impl Decodable for usize {
     fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
-        u64::decode(rlp).map(|value| value as usize)
+        let overflow = |len| DecoderError::IntegerOverflow {
+            type_name: "usize",
+            len,
+        };
+        let value = u64::decode(rlp).map_err(|err| match err {
+            DecoderError::IntegerOverflow {
+                len,
+                ..
+            } => overflow(len),
+            err => err,
+        })?;
+        usize::try_from(value).map_err(|_| overflow(8 - value.leading_zeros() as usize / 8))
     }
 }
 
@@ -256,6 +294,42 @@ impl_decodable_for_hash!(H256, 32);
 impl_decodable_for_hash!(H512, 64);
 impl_decodable_for_hash!(H520, 65);
 
+macro_rules! impl_codec_for_byte_array {
+    ($($size: expr),*) => {
+        $(
+            impl Encodable for [u8; $size] {
+                fn rlp_append(&self, s: &mut RlpStream) {
+                    s.encoder().encode_value(self);
+                }
+            }
+
+            impl Decodable for [u8; $size] {
+                fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+                    rlp.decoder().decode_value(|bytes| match bytes.len().cmp(&$size) {
+                        cmp::Ordering::Less => Err(DecoderError::RlpIsTooShort {
+                            expected: $size,
+                            got: bytes.len(),
+                        }),
+                        cmp::Ordering::Greater => Err(DecoderError::RlpIsTooBig {
+                            expected: $size,
+                            got: bytes.len(),
+                        }),
+                        cmp::Ordering::Equal => {
+                            let mut t = [0u8; $size];
+                            t.copy_from_slice(bytes);
+                            Ok(t)
+                        }
+                    })
+                }
+            }
+        )*
+    };
+}
+
+// The sizes listed in the crate documentation; other sizes have no implementation.
+impl_codec_for_byte_array!(1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16);
+impl_codec_for_byte_array!(17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 64, 65);
+
 macro_rules! impl_encodable_for_uint {
     ($name: ident, $size: expr) => {
         impl Encodable for $name {
@@ -273,18 +347,9 @@ macro_rules! impl_decodable_for_uint {
     ($name: ident, $size: expr) => {
         impl Decodable for $name {
             fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
-                rlp.decoder().decode_value(|bytes| {
-                    if !bytes.is_empty() && bytes[0] == 0 {
-                        Err(DecoderError::RlpInvalidIndirection)
-                    } else if bytes.len() <= $size {
-                        Ok($name::from(bytes))
-                    } else {
-                        Err(DecoderError::RlpIsTooBig {
-                            expected: $size,
-                            got: bytes.len(),
-                        })
-                    }
-                })
+                let profile = rlp.profile();
+                rlp.decoder()
+                    .decode_value(|bytes| integer_bytes(profile, bytes, stringify!($name), $size).map($name::from))
             }
         }
     };
@@ -308,14 +373,16 @@ impl Encodable for String {
 
 impl Decodable for String {
     fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        let profile = rlp.profile();
         rlp.decoder().decode_value(|bytes| {
-            if bytes.contains(&b'\0') {
+            if profile == DecodingProfile::Strict && bytes.contains(&b'\0') {
                 return Err(DecoderError::RlpNullTerminatedString)
             }
             match str::from_utf8(bytes) {
                 Ok(s) => Ok(s.to_owned()),
-                // consider better error type here
-                Err(_err) => Err(DecoderError::RlpExpectedToBeData),
+                Err(err) => Err(DecoderError::InvalidUtf8 {
+                    valid_up_to: err.valid_up_to(),
+                }),
             }
         })
     }
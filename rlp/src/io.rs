@@ -0,0 +1,240 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::{Decodable, DecoderError, Encodable, PayloadInfo};
+use std::error::Error as StdError;
+use std::fmt;
+use std::io::{self, ErrorKind, Read, Write};
+use std::marker::PhantomData;
+
+/// Error concerning `RlpReader`.
+#[derive(Debug)]
+pub enum ReadError {
+    /// The underlying reader failed.
+    Io(io::Error),
+    /// An item is invalid.
+    Decoder(DecoderError),
+    /// The reader ended in the middle of an item.
+    UnexpectedEof {
+        expected: usize,
+        got: usize,
+    },
+}
+
+impl StdError for ReadError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            ReadError::Io(err) => Some(err),
+            ReadError::Decoder(err) => Some(err),
+            ReadError::UnexpectedEof {
+                ..
+            } => None,
+        }
+    }
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReadError::Io(err) => write!(f, "io error: {}", err),
+            ReadError::Decoder(err) => write!(f, "invalid rlp: {}", err),
+            ReadError::UnexpectedEof {
+                expected,
+                got,
+            } => write!(f, "unexpected end of file in the middle of an item: expected {} bytes, got {}", expected, got),
+        }
+    }
+}
+
+impl From<io::Error> for ReadError {
+    fn from(err: io::Error) -> Self {
+        ReadError::Io(err)
+    }
+}
+
+impl From<DecoderError> for ReadError {
+    fn from(err: DecoderError) -> Self {
+        ReadError::Decoder(err)
+    }
+}
+
+/// Reads top-level rlp items written back to back.
+///
+/// ```rust
+/// fn main () {
+///     let mut bytes = rlp::encode(&"cat");
+///     bytes.extend(rlp::encode(&"dog"));
+///
+///     let reader = rlp::RlpReader::new(&bytes[..]);
+///     let animals: Result<Vec<String>, _> = reader.values().collect();
+///     assert_eq!(animals.unwrap(), vec!["cat".to_string(), "dog".to_string()]);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct RlpReader<R> {
+    reader: R,
+}
+
+impl<R> RlpReader<R>
+where
+    R: Read,
+{
+    pub fn new(reader: R) -> Self {
+        RlpReader {
+            reader,
+        }
+    }
+
+    /// Reads the next item including its header, or returns `None` at the end of the reader.
+    pub fn read_item(&mut self) -> Result<Option<Vec<u8>>, ReadError> {
+        let mut first = [0u8; 1];
+        if self.read_full(&mut first)? == 0 {
+            return Ok(None)
+        }
+
+        let header_len = PayloadInfo::header_len_of(first[0]);
+        let mut item = vec![0u8; header_len];
+        item[0] = first[0];
+        let got = 1 + self.read_full(&mut item[1..])?;
+        if got < header_len {
+            return Err(ReadError::UnexpectedEof {
+                expected: header_len,
+                got,
+            })
+        }
+
+        let info = PayloadInfo::from(&item)?;
+        let total = info.header_len.checked_add(info.value_len).ok_or(DecoderError::RlpInvalidLength {
+            expected: usize::MAX,
+            got: usize::MAX,
+        })?;
+        // The payload is read without preallocating it, so a corrupted header doesn't exhaust the memory.
+        let rest = (total - item.len()) as u64;
+        (&mut self.reader).take(rest).read_to_end(&mut item)?;
+        if item.len() < total {
+            return Err(ReadError::UnexpectedEof {
+                expected: total,
+                got: item.len(),
+            })
+        }
+        Ok(Some(item))
+    }
+
+    /// Reads and decodes the next item, or returns `None` at the end of the reader.
+    pub fn read<T>(&mut self) -> Result<Option<T>, ReadError>
+    where
+        T: Decodable, {
+        match self.read_item()? {
+            Some(item) => Ok(Some(crate::decode(&item)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Turns the reader into an iterator of decoded items.
+    pub fn values<T>(self) -> RlpValues<R, T>
+    where
+        T: Decodable, {
+        RlpValues {
+            reader: self,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Fills `buf` as far as possible and returns the number of bytes read.
+    fn read_full(&mut self, buf: &mut [u8]) -> Result<usize, io::Error> {
+        let mut read = 0;
+        while read < buf.len() {
+            match self.reader.read(&mut buf[read..]) {
+                Ok(0) => break,
+                Ok(n) => read += n,
+                Err(ref err) if err.kind() == ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(read)
+    }
+}
+
+impl<R> Iterator for RlpReader<R>
+where
+    R: Read,
+{
+    type Item = Result<Vec<u8>, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_item().transpose()
+    }
+}
+
+/// Iterator over the decoded items of `RlpReader`.
+#[derive(Debug)]
+pub struct RlpValues<R, T> {
+    reader: RlpReader<R>,
+    _marker: PhantomData<T>,
+}
+
+impl<R, T> Iterator for RlpValues<R, T>
+where
+    R: Read,
+    T: Decodable,
+{
+    type Item = Result<T, ReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.reader.read().transpose()
+    }
+}
+
+/// Writes top-level rlp items back to back.
+#[derive(Debug)]
+pub struct RlpWriter<W> {
+    writer: W,
+}
+
+impl<W> RlpWriter<W>
+where
+    W: Write,
+{
+    pub fn new(writer: W) -> Self {
+        RlpWriter {
+            writer,
+        }
+    }
+
+    /// Appends the encoded value.
+    pub fn write<E>(&mut self, value: &E) -> Result<(), io::Error>
+    where
+        E: Encodable, {
+        self.writer.write_all(&value.rlp_bytes())
+    }
+
+    /// Appends an already encoded item.
+    pub fn write_raw(&mut self, item: &[u8]) -> Result<(), io::Error> {
+        self.writer.write_all(item)
+    }
+
+    pub fn flush(&mut self) -> Result<(), io::Error> {
+        self.writer.flush()
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
@@ -0,0 +1,207 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::{Decodable, DecoderError, Encodable, Rlp, RlpStream};
+use primitives::{H128, H160, H256, H512, H520, U256};
+use rustc_hex::ToHex;
+use std::fmt;
+use std::ops::Index;
+
+/// Owned tree of rlp items.
+///
+/// ```rust
+/// use rlp::RlpItem;
+///
+/// fn main () {
+///     let item = RlpItem::List(vec![RlpItem::from("cat"), RlpItem::List(vec![RlpItem::from(1024u16)])]);
+///     assert_eq!(item.to_string(), "[\"0x636174\", [\"0x0400\"]]");
+///     assert_eq!(item[&[1, 0][..]], RlpItem::from(1024u16));
+///     assert_eq!(rlp::decode::<RlpItem>(&rlp::encode(&item)), Ok(item));
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RlpItem {
+    Data(Vec<u8>),
+    List(Vec<RlpItem>),
+}
+
+impl RlpItem {
+    /// Converts an encodable value into a tree.
+    ///
+    /// Panics if the value isn't encoded as a valid rlp.
+    pub fn from_value<E>(value: &E) -> Self
+    where
+        E: Encodable, {
+        crate::decode(&value.rlp_bytes()).expect("Encodable should encode a valid rlp")
+    }
+
+    pub fn is_data(&self) -> bool {
+        match self {
+            RlpItem::Data(_) => true,
+            RlpItem::List(_) => false,
+        }
+    }
+
+    pub fn is_list(&self) -> bool {
+        !self.is_data()
+    }
+
+    pub fn data(&self) -> Option<&[u8]> {
+        match self {
+            RlpItem::Data(data) => Some(data),
+            RlpItem::List(_) => None,
+        }
+    }
+
+    pub fn items(&self) -> Option<&[RlpItem]> {
+        match self {
+            RlpItem::Data(_) => None,
+            RlpItem::List(items) => Some(items),
+        }
+    }
+
+    /// Returns the item found by following indexes from the root.
+    pub fn at_path(&self, path: &[usize]) -> Option<&RlpItem> {
+        path.iter().try_fold(self, |item, &index| item.items()?.get(index))
+    }
+
+    /// Like `at_path`, but returns a mutable reference.
+    pub fn at_path_mut(&mut self, path: &[usize]) -> Option<&mut RlpItem> {
+        path.iter().try_fold(self, |item, &index| match item {
+            RlpItem::Data(_) => None,
+            RlpItem::List(items) => items.get_mut(index),
+        })
+    }
+
+    /// Decodes the tree as a value.
+    pub fn as_val<T>(&self) -> Result<T, DecoderError>
+    where
+        T: Decodable, {
+        crate::decode(&self.rlp_bytes())
+    }
+}
+
+impl Index<usize> for RlpItem {
+    type Output = RlpItem;
+
+    fn index(&self, index: usize) -> &RlpItem {
+        &self[&[index][..]]
+    }
+}
+
+impl<'a> Index<&'a [usize]> for RlpItem {
+    type Output = RlpItem;
+
+    fn index(&self, path: &'a [usize]) -> &RlpItem {
+        match self.at_path(path) {
+            Some(item) => item,
+            None => panic!("No item at {:?}", path),
+        }
+    }
+}
+
+impl fmt::Display for RlpItem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RlpItem::Data(data) => write!(f, "\"0x{}\"", data.to_hex()),
+            RlpItem::List(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+impl Encodable for RlpItem {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        match self {
+            RlpItem::Data(data) => s.encoder().encode_value(data),
+            RlpItem::List(items) => {
+                s.begin_list(items.len());
+                for item in items {
+                    s.append(item);
+                }
+            }
+        }
+    }
+}
+
+impl Decodable for RlpItem {
+    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        if !rlp.is_list() {
+            return rlp.as_val().map(RlpItem::Data)
+        }
+        let mut cursor = rlp.cursor()?;
+        let mut items = Vec::new();
+        while cursor.peek().is_some() {
+            items.push(cursor.next()?);
+        }
+        cursor.finish()?;
+        Ok(RlpItem::List(items))
+    }
+}
+
+impl<'a> From<&'a [u8]> for RlpItem {
+    fn from(data: &'a [u8]) -> Self {
+        RlpItem::Data(data.to_vec())
+    }
+}
+
+impl From<Vec<u8>> for RlpItem {
+    fn from(data: Vec<u8>) -> Self {
+        RlpItem::Data(data)
+    }
+}
+
+impl<'a> From<&'a str> for RlpItem {
+    fn from(s: &'a str) -> Self {
+        RlpItem::Data(s.as_bytes().to_vec())
+    }
+}
+
+impl From<String> for RlpItem {
+    fn from(s: String) -> Self {
+        RlpItem::Data(s.into_bytes())
+    }
+}
+
+impl From<Vec<RlpItem>> for RlpItem {
+    fn from(items: Vec<RlpItem>) -> Self {
+        RlpItem::List(items)
+    }
+}
+
+macro_rules! impl_from_for_encodable {
+    ($name: ident) => {
+        impl From<$name> for RlpItem {
+            fn from(value: $name) -> Self {
+                RlpItem::from_value(&value)
+            }
+        }
+    };
+}
+
+impl_from_for_encodable!(bool);
+impl_from_for_encodable!(u8);
+impl_from_for_encodable!(u16);
+impl_from_for_encodable!(u32);
+impl_from_for_encodable!(u64);
+impl_from_for_encodable!(u128);
+impl_from_for_encodable!(usize);
+impl_from_for_encodable!(U256);
+impl_from_for_encodable!(H128);
+impl_from_for_encodable!(H160);
+impl_from_for_encodable!(H256);
+impl_from_for_encodable!(H512);
+impl_from_for_encodable!(H520);
@@ -11,6 +11,11 @@
 //!
 //! Allows encoding, decoding, and view onto rlp-slice
 //!
+//! The decoding shortcuts return `Result` and never panic on malformed input.
+//! The encoding shortcuts cannot fail on their input;
+//! they panic only if an `Encodable` implementation appends more or fewer items than it declares,
+//! or if a `None` encoded with `OptionAsTrailing` is followed by `Some`.
+//!
 //!# What should you use when?
 //!
 //!### Use `encode` function when:
@@ -32,15 +37,86 @@
 //! * You are working on input data.
 //! * You want to get view onto rlp-slice.
 //! * You don't want to decode whole rlp at once.
+//!
+//!### Use `DecodingProfile` when:
+//! * You decode data encoded by another implementation, e.g. an Ethereum client.
+//! * You read data written before the rules became strict, e.g. an old database.
+//!
+//!### Use `RlpEditor` when:
+//! * You want to change a few items of an encoded rlp.
+//! * You don't want to decode and encode whole rlp again.
+//!
+//!### Use `RlpReader` and `RlpWriter` when:
+//! * You read or write rlp items stored back to back, e.g. in a file.
+//! * You don't want to load all items at once.
+//!
+//!### Use `RlpFile` (requires the `mmap` feature) when:
+//! * You navigate a file too large to be read at once.
+//! * You want to decode only a few items of it.
+//!
+//!### Use `RlpCodec` (requires the `tokio` feature) when:
+//! * You exchange rlp items over an async byte stream.
+//! * You want each item as a separate frame.
+//!
+//!# Upgrading from 0.5
+//!
+//! * `decode` and `Rlp::new` follow `DecodingProfile::Strict`, so `bool` accepts only `0x00` and `0x01`.
+//!   The empty data `0x80`, which Ethereum clients encode as `false`, and bytes other than 0 and 1 are rejected.
+//!   Use `decode_with_profile` or `Rlp::with_profile` with `DecodingProfile::Ethereum` or `DecodingProfile::Legacy`
+//!   to accept them.
+//! * `Vec<T>` and `&[T]` are `Encodable` for any `Encodable` item,
+//!   and `Vec<T>` is `Decodable` for any `Decodable` item.
+//!   `Vec<u8>` and `&[u8]` are still encoded as data and the others as lists.
+//!   Integer literals no longer default to `u8`, so `stream.append(&vec![1, 2])` needs a suffix, e.g. `vec![1u8, 2]`.
+//!   A `Vec<u8>` field of a type deriving `RlpEncodable` is encoded as data too, not as a list of integers.
+//!   Add `#[rlp(with = "rlp::byte_list")]` to the field to keep reading and writing the list form.
+//! * `decode_list` returns a `Result` instead of panicking on malformed input.
+//!   It also fails on a malformed item where it used to stop at it.
+//! * `DecoderError::Custom` holds a `String` instead of a `&'static str`; create it with `DecoderError::custom`.
+//! * `DecoderError` has the new variants `InvalidUtf8`, `IntegerOverflow`, `LeadingZeroInteger`, `InvalidBool`,
+//!   `UnknownTag` and `Context`, so an exhaustive `match` on it needs new arms or a wildcard.
+//! * `DecoderError` no longer implements the deprecated `description()`, which now returns the default text of
+//!   `std::error::Error`. Use `to_string()`, which gives a message instead of the `Debug` output.
+//! * `[u8; N]` is encoded as data of exactly `N` bytes.
+//!   It implements `Encodable`, `Decodable` and `RlpSchema` for `N` from 1 to 32, 64 and 65 only;
+//!   wrap other sizes in a type implementing the traits.
 
+pub mod byte_list;
+mod canonical;
+#[cfg(feature = "tokio")]
+mod codec;
+mod cursor;
+mod diff;
+mod editor;
 mod error;
 mod impls;
+mod io;
+mod item;
+mod macros;
+#[cfg(feature = "mmap")]
+mod mmap;
+mod option;
+mod profile;
 mod rlpin;
+mod schema;
 mod stream;
 mod traits;
 
-pub use error::DecoderError;
+pub use canonical::{CanonicalFix, CanonicalFixKind};
+#[cfg(feature = "tokio")]
+pub use codec::{CodecError, RlpCodec, DEFAULT_MAX_FRAME_SIZE};
+pub use cursor::RlpCursor;
+pub use diff::{Difference, DifferenceKind};
+pub use editor::RlpEditor;
+pub use error::{DecoderError, DecoderResultExt};
+pub use io::{ReadError, RlpReader, RlpValues, RlpWriter};
+pub use item::RlpItem;
+#[cfg(feature = "mmap")]
+pub use mmap::{RlpFile, RlpItems};
+pub use option::{OptionAsEmptyData, OptionAsTrailing};
+pub use profile::DecodingProfile;
 pub use rlpin::{PayloadInfo, Prototype, Rlp, RlpIterator};
+pub use schema::{ParseSchemaError, RlpSchema, Schema, SchemaMismatch};
 use std::borrow::Borrow;
 pub use stream::RlpStream;
 pub use traits::{Decodable, Encodable};
@@ -50,7 +126,7 @@ pub const NULL_RLP: [u8; 1] = [0x80; 1];
 /// The RLP encoded empty list.
 pub const EMPTY_LIST_RLP: [u8; 1] = [0xC0; 1];
 
-/// Shortcut function to decode trusted rlp
+/// Shortcut function to decode rlp
 ///
 /// ```rust
 /// fn main () {
@@ -66,11 +142,117 @@ where
     rlp.as_val()
 }
 
-pub fn decode_list<T>(bytes: &[u8]) -> Vec<T>
+/// Shortcut function to decode rlp with the given profile.
+///
+/// ```rust
+/// use rlp::DecodingProfile;
+///
+/// fn main () {
+///     let data = vec![0x80];
+///     assert!(rlp::decode::<bool>(&data).is_err());
+///     assert_eq!(rlp::decode_with_profile::<bool>(&data, DecodingProfile::Ethereum), Ok(false));
+/// }
+/// ```
+pub fn decode_with_profile<T>(bytes: &[u8], profile: DecodingProfile) -> Result<T, DecoderError>
+where
+    T: Decodable, {
+    let rlp = Rlp::with_profile(bytes, profile);
+    rlp.as_val()
+}
+
+/// Shortcut function to compare two rlps item by item.
+///
+/// ```rust
+/// fn main () {
+///     let a = rlp::encode_list::<u8, _>(&[1, 2, 3]);
+///     let b = rlp::encode_list::<u8, _>(&[1, 4]);
+///     let differences = rlp::diff(&a, &b).expect("could not compare");
+///     let rendered: Vec<_> = differences.iter().map(ToString::to_string).collect();
+///     assert_eq!(rendered, vec![
+///         "[]: length mismatch, [\"0x01\", \"0x02\", \"0x03\"] != [\"0x01\", \"0x04\"]",
+///         "[1]: changed data, \"0x02\" != \"0x04\"",
+///     ]);
+/// }
+/// ```
+pub fn diff<'a>(a: &'a [u8], b: &'a [u8]) -> Result<Vec<Difference<'a>>, DecoderError> {
+    let mut differences = Vec::new();
+    diff::diff_items(&mut Vec::new(), &Rlp::new(a), &Rlp::new(b), &mut differences)?;
+    Ok(differences)
+}
+
+/// Shortcut function to re-encode an rlp in the canonical form.
+///
+/// Unlike `decode`, non-canonical headers are accepted. Every fix made is returned with the result.
+///
+/// ```rust
+/// fn main () {
+///     let legacy = vec![0xc3, 0x81, 0x05, 0x06];
+///     let (canonical, fixes) = rlp::canonicalize(&legacy).expect("could not canonicalize");
+///     assert_eq!(canonical, vec![0xc2, 0x05, 0x06]);
+///     assert_eq!(fixes[0].to_string(), "[0]: single byte indirection");
+/// }
+/// ```
+pub fn canonicalize(bytes: &[u8]) -> Result<(Vec<u8>, Vec<CanonicalFix>), DecoderError> {
+    let mut stream = RlpStream::new();
+    let mut fixes = Vec::new();
+    let consumed = canonical::canonicalize_item(&mut stream, &mut Vec::new(), bytes, &mut fixes)?;
+    if consumed != bytes.len() {
+        return Err(DecoderError::RlpIsTooBig {
+            expected: consumed,
+            got: bytes.len(),
+        })
+    }
+    Ok((stream.out(), fixes))
+}
+
+/// Shortcut function to decode a list.
+///
+/// Unlike `Rlp::as_list`, it fails on a malformed list instead of stopping at it.
+///
+/// ```rust
+/// fn main () {
+///     let data = vec![0xc3, 0x01, 0x02, 0x03];
+///     let numbers: Vec<u8> = rlp::decode_list(&data).expect("could not decode");
+///     assert_eq!(numbers, vec![1, 2, 3]);
+///     assert!(rlp::decode_list::<u8>(&[0xc3, 0x01, 0x02]).is_err());
+/// }
+/// ```
+pub fn decode_list<T>(bytes: &[u8]) -> Result<Vec<T>, DecoderError>
 where
     T: Decodable, {
     let rlp = Rlp::new(bytes);
-    rlp.as_list().expect("trusted rlp should be valid")
+    let mut cursor = rlp.cursor()?;
+    let mut list = Vec::new();
+    while cursor.peek().is_some() {
+        list.push(cursor.next()?);
+    }
+    cursor.finish()?;
+    Ok(list)
+}
+
+/// Shortcut function to decode a list of exactly `len` items.
+///
+/// ```rust
+/// fn main () {
+///     let data = vec![0xc3, 0x01, 0x02, 0x03];
+///     assert_eq!(rlp::decode_list_exact::<u8>(&data, 3), Ok(vec![1, 2, 3]));
+///     assert_eq!(rlp::decode_list_exact::<u8>(&data, 2), Err(rlp::DecoderError::RlpIncorrectListLen {
+///         expected: 2,
+///         got: 3,
+///     }));
+/// }
+/// ```
+pub fn decode_list_exact<T>(bytes: &[u8], len: usize) -> Result<Vec<T>, DecoderError>
+where
+    T: Decodable, {
+    let list = decode_list(bytes)?;
+    if list.len() != len {
+        return Err(DecoderError::RlpIncorrectListLen {
+            expected: len,
+            got: list.len(),
+        })
+    }
+    Ok(list)
 }
 
 /// Shortcut function to encode structure into rlp.
@@ -90,6 +272,14 @@ where
     stream.drain()
 }
 
+/// Shortcut function to encode a slice into an rlp list.
+///
+/// ```rust
+/// fn main () {
+///     let out = rlp::encode_list::<u8, _>(&[1, 2, 3]);
+///     assert_eq!(out, vec![0xc3, 0x01, 0x02, 0x03]);
+/// }
+/// ```
 pub fn encode_list<E, K>(object: &[K]) -> Vec<u8>
 where
     E: Encodable,
@@ -98,3 +288,25 @@ where
     stream.append_list(object);
     stream.drain()
 }
+
+/// Shortcut function to encode the items of an iterator into an rlp list.
+///
+/// ```rust
+/// fn main () {
+///     let out = rlp::encode_iter::<u8, _, _>((1..4).map(|i| i * 2));
+///     assert_eq!(out, vec![0xc3, 0x02, 0x04, 0x06]);
+/// }
+/// ```
+pub fn encode_iter<E, K, I>(iter: I) -> Vec<u8>
+where
+    E: Encodable,
+    K: Borrow<E>,
+    I: IntoIterator<Item = K>, {
+    let mut stream = RlpStream::new();
+    stream.begin_unbounded_list();
+    for item in iter {
+        stream.append(item.borrow());
+    }
+    stream.complete_unbounded_list();
+    stream.drain()
+}
@@ -0,0 +1,68 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/// Builds an `RlpItem` list from its elements.
+///
+/// Brackets nest as lists, `bytes!(..)` is data made of the given bytes,
+/// and any other expression is converted by its `Encodable` implementation.
+///
+/// ```rust
+/// use rlp::{rlp_item, RlpItem};
+///
+/// fn main () {
+///     let item = rlp_item![1u64, "cat", [[], bytes!(0x01, 0x02)]];
+///     assert_eq!(item, RlpItem::List(vec![
+///         RlpItem::from(1u64),
+///         RlpItem::from("cat"),
+///         RlpItem::List(vec![RlpItem::List(vec![]), RlpItem::Data(vec![0x01, 0x02])]),
+///     ]));
+/// }
+/// ```
+#[macro_export]
+macro_rules! rlp_item {
+    (@list [$($out:expr,)*]) => {
+        vec![$($out),*]
+    };
+    (@list [$($out:expr,)*] [$($inner:tt)*] $(, $($rest:tt)*)?) => {
+        $crate::rlp_item!(@list [$($out,)* $crate::rlp_item![$($inner)*],] $($($rest)*)?)
+    };
+    (@list [$($out:expr,)*] bytes!($($byte:expr),* $(,)?) $(, $($rest:tt)*)?) => {
+        $crate::rlp_item!(@list [$($out,)* {
+            let bytes: Vec<u8> = vec![$($byte),*];
+            $crate::RlpItem::Data(bytes)
+        },] $($($rest)*)?)
+    };
+    (@list [$($out:expr,)*] $value:expr $(, $($rest:tt)*)?) => {
+        $crate::rlp_item!(@list [$($out,)* $crate::RlpItem::from_value(&$value),] $($($rest)*)?)
+    };
+    ($($elements:tt)*) => {
+        $crate::RlpItem::List($crate::rlp_item!(@list [] $($elements)*))
+    };
+}
+
+/// Encodes a list written like `rlp_item!` into bytes.
+///
+/// ```rust
+/// use primitives::H256;
+/// use rlp::{rlp, RlpStream};
+///
+/// fn main () {
+///     let mut stream = RlpStream::new_list(4);
+///     stream.append(&1u64).append(&"cat");
+///     stream.begin_list(2).append(&H256::zero()).begin_list(0);
+///     stream.append(&vec![0x01u8, 0x02]);
+///
+///     assert_eq!(rlp![1u64, "cat", [H256::zero(), []], bytes!(0x01, 0x02)], stream.out());
+/// }
+/// ```
+#[macro_export]
+macro_rules! rlp {
+    ($($elements:tt)*) => {
+        $crate::encode(&$crate::rlp_item![$($elements)*])
+    };
+}
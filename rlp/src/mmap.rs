@@ -0,0 +1,100 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::{DecoderError, PayloadInfo, Rlp};
+use memmap::Mmap;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+/// A file mapped into memory and viewed as rlp.
+///
+/// Only the pages touched while navigating the rlp are read from the disk.
+/// The file must not be modified while it is mapped.
+#[derive(Debug)]
+pub struct RlpFile {
+    // An empty file cannot be mapped.
+    mmap: Option<Mmap>,
+}
+
+impl RlpFile {
+    pub fn open<P>(path: P) -> Result<Self, io::Error>
+    where
+        P: AsRef<Path>, {
+        let file = File::open(path)?;
+        if file.metadata()?.len() == 0 {
+            return Ok(RlpFile {
+                mmap: None,
+            })
+        }
+        // The mapping is only read, and modifying a mapped file is documented as unsupported.
+        let mmap = unsafe { Mmap::map(&file)? };
+        Ok(RlpFile {
+            mmap: Some(mmap),
+        })
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        match &self.mmap {
+            Some(mmap) => &mmap[..],
+            None => &[],
+        }
+    }
+
+    /// Views the whole file as one rlp.
+    pub fn rlp(&self) -> Rlp<'_> {
+        Rlp::new(self.as_bytes())
+    }
+
+    /// Iterates the top-level items stored back to back in the file.
+    pub fn items(&self) -> RlpItems<'_> {
+        RlpItems {
+            bytes: self.as_bytes(),
+        }
+    }
+}
+
+/// Iterator over the top-level items of `RlpFile`.
+///
+/// It stops after the first invalid item.
+#[derive(Debug, Clone)]
+pub struct RlpItems<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Iterator for RlpItems<'a> {
+    type Item = Result<Rlp<'a>, DecoderError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bytes.is_empty() {
+            return None
+        }
+        let total = match PayloadInfo::from(self.bytes) {
+            Ok(info) => info.header_len.checked_add(info.value_len),
+            Err(err) => {
+                self.bytes = &[];
+                return Some(Err(err))
+            }
+        };
+        match total {
+            Some(total) if total <= self.bytes.len() => {
+                let (item, rest) = self.bytes.split_at(total);
+                self.bytes = rest;
+                Some(Ok(Rlp::new(item)))
+            }
+            _ => {
+                let err = DecoderError::RlpIsTooShort {
+                    expected: total.unwrap_or(usize::MAX),
+                    got: self.bytes.len(),
+                };
+                self.bytes = &[];
+                Some(Err(err))
+            }
+        }
+    }
+}
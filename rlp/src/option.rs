@@ -0,0 +1,128 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::{Decodable, DecoderError, Encodable, Rlp, RlpStream};
+
+/// `Option` encoded as empty data when it is `None` and as the value itself when it is `Some`.
+///
+/// `Option` itself is encoded as a list of zero or one item.
+/// A value encoded as empty data, e.g. `0` or `""`, is decoded as `None`.
+///
+/// ```rust
+/// use rlp::OptionAsEmptyData;
+///
+/// fn main () {
+///     assert_eq!(rlp::encode(&OptionAsEmptyData::<u8>(None)), vec![0x80]);
+///     assert_eq!(rlp::encode(&OptionAsEmptyData(Some(7u8))), vec![0x07]);
+///     assert_eq!(rlp::decode(&[0x80]), Ok(OptionAsEmptyData::<u8>(None)));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OptionAsEmptyData<T>(pub Option<T>);
+
+impl<T> OptionAsEmptyData<T>
+where
+    T: Encodable,
+{
+    /// Appends the value to a list the way `OptionAsEmptyData` encodes it, without taking it.
+    pub fn append(stream: &mut RlpStream, value: &Option<T>) {
+        match value {
+            Some(value) => stream.append(value),
+            None => stream.append_empty_data(),
+        };
+    }
+}
+
+impl<T> Encodable for OptionAsEmptyData<T>
+where
+    T: Encodable,
+{
+    fn rlp_append(&self, s: &mut RlpStream) {
+        match &self.0 {
+            Some(value) => value.rlp_append(s),
+            None => s.encoder().encode_value(&[]),
+        }
+    }
+}
+
+impl<T> Decodable for OptionAsEmptyData<T>
+where
+    T: Decodable,
+{
+    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        if rlp.is_data() && rlp.is_empty() {
+            return Ok(OptionAsEmptyData(None))
+        }
+        rlp.as_val().map(|value| OptionAsEmptyData(Some(value)))
+    }
+}
+
+impl<T> From<Option<T>> for OptionAsEmptyData<T> {
+    fn from(value: Option<T>) -> Self {
+        OptionAsEmptyData(value)
+    }
+}
+
+impl<T> From<OptionAsEmptyData<T>> for Option<T> {
+    fn from(value: OptionAsEmptyData<T>) -> Self {
+        value.0
+    }
+}
+
+/// `Option` items at the end of a list, omitted when they are `None`.
+///
+/// An item is `None` if the list ends before it.
+/// Only the `None` items after the last `Some` can be omitted,
+/// so encoding panics when a `None` is followed by `Some`.
+///
+/// ```rust
+/// use rlp::{OptionAsTrailing, Rlp, RlpStream};
+///
+/// fn main () {
+///     let (name, legs): (&str, Option<u8>) = ("cat", None);
+///     let mut stream = RlpStream::new_list(1 + OptionAsTrailing::count(&[legs.is_some()]));
+///     stream.append(&name);
+///     OptionAsTrailing::append(&mut stream, &legs);
+///     let out = stream.out();
+///     assert_eq!(out, vec![0xc4, 0x83, b'c', b'a', b't']);
+///     assert_eq!(OptionAsTrailing::decode::<u8>(&Rlp::new(&out), 1), Ok(None));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OptionAsTrailing;
+
+impl OptionAsTrailing {
+    /// The number of trailing items to append, given whether each of them is `Some`.
+    ///
+    /// Panics if `None` is followed by `Some`, since such `None` cannot be omitted.
+    pub fn count(present: &[bool]) -> usize {
+        let count = present.iter().take_while(|&&present| present).count();
+        assert!(present[count..].iter().all(|&present| !present), "a trailing None cannot be followed by Some");
+        count
+    }
+
+    /// Appends the value to a list unless it is `None`.
+    pub fn append<T>(stream: &mut RlpStream, value: &Option<T>)
+    where
+        T: Encodable, {
+        if let Some(value) = value {
+            stream.append(value);
+        }
+    }
+
+    /// Decodes the item at `index` of the list, or returns `None` if the list is shorter.
+    pub fn decode<T>(rlp: &Rlp, index: usize) -> Result<Option<T>, DecoderError>
+    where
+        T: Decodable, {
+        if index < rlp.item_count()? {
+            rlp.val_at(index).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
@@ -0,0 +1,44 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/// The rules the built-in `Decodable` implementations follow.
+///
+/// | value               | `Strict`         | `Ethereum`       | `Legacy`                   |
+/// |---------------------|------------------|------------------|----------------------------|
+/// | `bool`              | `0x00`, `0x01`   | `0x80`, `0x01`   | empty or any single byte   |
+/// | integer leading zero| rejected         | rejected         | ignored                    |
+/// | `String` with NUL   | rejected         | accepted         | accepted                   |
+///
+/// Headers are checked the same way by every profile; use `canonicalize` to repair them.
+/// A profile is set on the root `Rlp` and inherited by every item taken from it.
+///
+/// ```rust
+/// use rlp::{DecodingProfile, Rlp};
+///
+/// fn main () {
+///     let data = vec![0xc3, 0x82, 0x00, 0x01];
+///     assert!(Rlp::new(&data).val_at::<u16>(0).is_err());
+///     let legacy = Rlp::with_profile(&data, DecodingProfile::Legacy);
+///     assert_eq!(legacy.val_at::<u16>(0), Ok(1));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DecodingProfile {
+    /// Accepts only what this crate encodes.
+    Strict,
+    /// Accepts what Ethereum clients encode.
+    Ethereum,
+    /// Accepts loosely encoded values, e.g. the ones found in old databases.
+    Legacy,
+}
+
+impl Default for DecodingProfile {
+    fn default() -> Self {
+        DecodingProfile::Strict
+    }
+}
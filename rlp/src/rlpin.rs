@@ -8,10 +8,13 @@
 // except according to those terms.
 
 use super::impls::decode_usize;
-use super::{Decodable, DecoderError};
+use super::{CanonicalFixKind, Decodable, DecoderError, DecodingProfile, RlpCursor};
 use rustc_hex::ToHex;
 use std::cell::Cell;
-use std::fmt;
+use std::collections::VecDeque;
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::{fmt, mem};
 
 /// rlp offset
 #[derive(Copy, Clone, Debug)]
@@ -74,6 +77,37 @@ fn calculate_payload_info(header_bytes: &[u8], len_of_len: usize) -> Result<Payl
     Ok(PayloadInfo::new(header_len, value_len))
 }
 
+fn calculate_lenient_payload_info(
+    header_bytes: &[u8],
+    len_of_len: usize,
+    fixes: &mut Vec<CanonicalFixKind>,
+) -> Result<PayloadInfo, DecoderError> {
+    let header_len = 1 + len_of_len;
+    if header_bytes.len() < header_len {
+        return Err(DecoderError::RlpIsTooShort {
+            expected: header_len,
+            got: header_bytes.len(),
+        })
+    }
+    let len_bytes = &header_bytes[1..header_len];
+    let leading_zeros = len_bytes.iter().take_while(|&&byte| byte == 0).count();
+    if leading_zeros != 0 {
+        fixes.push(CanonicalFixKind::LengthWithZeroPrefix);
+    }
+    let expected = mem::size_of::<usize>();
+    if len_of_len - leading_zeros > expected {
+        return Err(DecoderError::RlpIsTooBig {
+            expected,
+            got: len_of_len - leading_zeros,
+        })
+    }
+    let value_len = len_bytes[leading_zeros..].iter().fold(0usize, |len, &byte| (len << 8) | byte as usize);
+    if value_len <= 55 {
+        fixes.push(CanonicalFixKind::LongFormHeader);
+    }
+    Ok(PayloadInfo::new(header_len, value_len))
+}
+
 impl PayloadInfo {
     fn new(header_len: usize, value_len: usize) -> PayloadInfo {
         PayloadInfo {
@@ -107,6 +141,55 @@ impl PayloadInfo {
             }
         }
     }
+
+    /// The number of bytes `from` needs to read the header starting with `first`.
+    pub(crate) fn header_len_of(first: u8) -> usize {
+        match first {
+            0xb8..=0xbf => 1 + (first - 0xb7) as usize,
+            0xf8..=0xff => 1 + (first - 0xf7) as usize,
+            _ => 1,
+        }
+    }
+
+    /// Create a new object from the given bytes RLP like `from`, but accept non-canonical headers.
+    ///
+    /// Returns every violation of the canonical form found in the header,
+    /// and the bytes must contain the whole payload.
+    pub fn from_lenient(bytes: &[u8]) -> Result<(PayloadInfo, Vec<CanonicalFixKind>), DecoderError> {
+        let mut fixes = Vec::new();
+        let info = match bytes.first().cloned() {
+            None => {
+                return Err(DecoderError::RlpIsTooShort {
+                    expected: 1,
+                    got: 0,
+                })
+            }
+            Some(0..=0x7f) => PayloadInfo::new(0, 1),
+            Some(l @ 0x80..=0xb7) => PayloadInfo::new(1, l as usize - 0x80),
+            Some(l @ 0xb8..=0xbf) => calculate_lenient_payload_info(bytes, l as usize - 0xb7, &mut fixes)?,
+            Some(l @ 0xc0..=0xf7) => PayloadInfo::new(1, l as usize - 0xc0),
+            Some(l @ 0xf8..=0xff) => calculate_lenient_payload_info(bytes, l as usize - 0xf7, &mut fixes)?,
+        };
+        match info.header_len.checked_add(info.value_len) {
+            Some(total) if total <= bytes.len() => {}
+            Some(total) => {
+                return Err(DecoderError::RlpIsTooShort {
+                    expected: total,
+                    got: bytes.len(),
+                })
+            }
+            None => {
+                return Err(DecoderError::RlpInvalidLength {
+                    expected: usize::max_value(),
+                    got: usize::max_value(),
+                })
+            }
+        }
+        if bytes[0] < 0xc0 && info.header_len != 0 && info.value_len == 1 && bytes[info.header_len] < 0x80 {
+            fixes.push(CanonicalFixKind::SingleByteIndirection);
+        }
+        Ok((info, fixes))
+    }
 }
 
 /// Data-oriented view onto rlp-slice.
@@ -118,6 +201,7 @@ impl PayloadInfo {
 #[derive(Debug)]
 pub struct Rlp<'a> {
     bytes: &'a [u8],
+    profile: DecodingProfile,
     offset_cache: Cell<Option<OffsetCache>>,
     count_cache: Cell<Option<usize>>,
 }
@@ -126,6 +210,7 @@ impl<'a> Clone for Rlp<'a> {
     fn clone(&self) -> Rlp<'a> {
         Rlp {
             bytes: self.bytes,
+            profile: self.profile,
             offset_cache: self.offset_cache.clone(),
             count_cache: self.count_cache.clone(),
         }
@@ -157,13 +242,23 @@ where
     'a: 'view,
 {
     pub fn new(bytes: &'a [u8]) -> Rlp<'a> {
+        Rlp::with_profile(bytes, DecodingProfile::default())
+    }
+
+    /// Creates a view decoding its items with the given profile.
+    pub fn with_profile(bytes: &'a [u8], profile: DecodingProfile) -> Rlp<'a> {
         Rlp {
             bytes,
+            profile,
             offset_cache: Cell::new(None),
             count_cache: Cell::new(None),
         }
     }
 
+    pub fn profile(&self) -> DecodingProfile {
+        self.profile
+    }
+
     pub fn as_raw(&'view self) -> &'a [u8] {
         self.bytes
     }
@@ -199,6 +294,16 @@ where
         }))
     }
 
+    /// Like `item_count`, but fails on a malformed item instead of stopping at it.
+    pub(crate) fn strict_item_count(&self) -> Result<usize, DecoderError> {
+        let count = self.item_count()?;
+        let consumed: usize = self.iter().map(|item| item.as_raw().len()).sum();
+        if consumed != self.payload_info()?.value_len {
+            self.at(count)?;
+        }
+        Ok(count)
+    }
+
     pub fn size(&self) -> usize {
         // TODO: No panic on malformed data, but ideally would Err on no PayloadInfo.
         if !self.is_data() {
@@ -243,9 +348,15 @@ where
         // update the cache
         self.offset_cache.set(Some(OffsetCache::new(index, new_offset)));
 
-        // construct new rlp
+        // construct new rlp, which must end within the payload as well
         let found = BasicDecoder::payload_info(bytes)?;
-        Ok(Rlp::new(&bytes[0..found.header_len + found.value_len]))
+        if new_offset + found.total() > offset_max + 1 {
+            return Err(DecoderError::RlpIsTooShort {
+                expected: new_offset + found.total(),
+                got: offset_max + 1,
+            })
+        }
+        Ok(Rlp::with_profile(&bytes[0..found.header_len + found.value_len], self.profile))
     }
 
     pub fn is_null(&self) -> bool {
@@ -284,6 +395,11 @@ where
         self.into_iter()
     }
 
+    /// Creates a cursor reading the items of the list one by one.
+    pub fn cursor(&self) -> Result<RlpCursor<'a>, DecoderError> {
+        RlpCursor::new(self)
+    }
+
     pub fn as_val<T>(&self) -> Result<T, DecoderError>
     where
         T: Decodable, {
@@ -293,7 +409,53 @@ where
     pub fn as_list<T>(&self) -> Result<Vec<T>, DecoderError>
     where
         T: Decodable, {
-        self.iter().map(|rlp| rlp.as_val()).collect()
+        // A loop rather than `collect`, so the size of the list is not counted before walking it.
+        let mut list = Vec::new();
+        for item in self.iter() {
+            list.push(item.as_val()?);
+        }
+        Ok(list)
+    }
+
+    /// Decodes the items of the list in parallel.
+    ///
+    /// Unlike `as_list`, it fails on a malformed list instead of stopping at it.
+    /// If several items are invalid, the error of the one with the lowest index is returned.
+    #[cfg(feature = "rayon")]
+    pub fn par_as_list<T>(&self) -> Result<Vec<T>, DecoderError>
+    where
+        T: Decodable + Send, {
+        use rayon::prelude::*;
+
+        if !self.is_list() {
+            return Err(DecoderError::RlpExpectedToBeList)
+        }
+        // Index the items first, since an item can be found only after the previous one.
+        let (mut bytes, _) = self.consume_list_payload()?;
+        let mut items = Vec::new();
+        let mut index_error = None;
+        while !bytes.is_empty() {
+            match BasicDecoder::payload_info(bytes) {
+                Ok(info) => {
+                    let (item, rest) = bytes.split_at(info.total());
+                    items.push(item);
+                    bytes = rest;
+                }
+                Err(err) => {
+                    index_error = Some(err);
+                    break
+                }
+            }
+        }
+
+        let profile = self.profile;
+        let decoded: Vec<Result<T, DecoderError>> =
+            items.into_par_iter().map(|item| Rlp::with_profile(item, profile).as_val()).collect();
+        let list = decoded.into_iter().collect::<Result<Vec<_>, _>>()?;
+        match index_error {
+            Some(err) => Err(err),
+            None => Ok(list),
+        }
     }
 
     pub fn val_at<T>(&self, index: usize) -> Result<T, DecoderError>
@@ -353,11 +515,64 @@ where
 }
 
 /// Iterator over rlp-slice list elements.
+///
+/// It walks the payload of the list once and stops at the first malformed item.
+/// The items are indexed only when they are taken from the back.
 pub struct RlpIterator<'a, 'view>
 where
     'a: 'view, {
-    rlp: &'view Rlp<'a>,
-    index: usize,
+    /// The payload not yet walked from the front.
+    payload: &'a [u8],
+    /// The number of items in `payload` once it is known, counted at most once by `size_hint`.
+    count: Cell<Option<usize>>,
+    profile: DecodingProfile,
+    /// The items left after indexing the payload for `next_back`.
+    indexed: Option<VecDeque<Rlp<'a>>>,
+    _view: PhantomData<&'view Rlp<'a>>,
+}
+
+impl<'a, 'view> RlpIterator<'a, 'view>
+where
+    'a: 'view,
+{
+    /// Splits the first item off the payload.
+    fn split_first(&mut self) -> Option<Rlp<'a>> {
+        if self.payload.is_empty() {
+            return None
+        }
+        match BasicDecoder::payload_info(self.payload) {
+            Ok(info) => {
+                let (item, rest) = self.payload.split_at(info.total());
+                self.payload = rest;
+                Some(Rlp::with_profile(item, self.profile))
+            }
+            Err(_) => {
+                self.payload = &[];
+                None
+            }
+        }
+    }
+
+    fn indexed(&mut self) -> &mut VecDeque<Rlp<'a>> {
+        if self.indexed.is_none() {
+            let mut items = VecDeque::with_capacity(self.count.get().unwrap_or(0));
+            while let Some(item) = self.split_first() {
+                items.push_back(item);
+            }
+            self.indexed = Some(items);
+        }
+        self.indexed.as_mut().expect("indexed above; qed")
+    }
+}
+
+/// The number of items at the front of `payload` before the first malformed one.
+fn count_items(mut payload: &[u8]) -> usize {
+    let mut count = 0;
+    while let Ok(info) = BasicDecoder::payload_info(payload) {
+        payload = &payload[info.total()..];
+        count += 1;
+    }
+    count
 }
 
 impl<'a, 'view> IntoIterator for &'view Rlp<'a>
@@ -368,9 +583,17 @@ where
     type IntoIter = RlpIterator<'a, 'view>;
 
     fn into_iter(self) -> Self::IntoIter {
+        let payload = if self.is_list() {
+            self.consume_list_payload().map(|(payload, _)| payload).unwrap_or(&[])
+        } else {
+            &[]
+        };
         RlpIterator {
-            rlp: self,
-            index: 0,
+            payload,
+            count: Cell::new(self.count_cache.get()),
+            profile: self.profile,
+            indexed: None,
+            _view: PhantomData,
         }
     }
 }
@@ -379,13 +602,41 @@ impl<'a, 'view> Iterator for RlpIterator<'a, 'view> {
     type Item = Rlp<'a>;
 
     fn next(&mut self) -> Option<Rlp<'a>> {
-        let index = self.index;
-        let result = self.rlp.at(index).ok();
-        self.index += 1;
-        result
+        if let Some(indexed) = self.indexed.as_mut() {
+            return indexed.pop_front()
+        }
+        let item = self.split_first();
+        if item.is_some() {
+            let count = self.count.get_mut();
+            *count = count.map(|count| count - 1);
+        }
+        item
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = match (&self.indexed, self.count.get()) {
+            (Some(indexed), _) => indexed.len(),
+            (None, Some(count)) => count,
+            (None, None) => {
+                let count = count_items(self.payload);
+                self.count.set(Some(count));
+                count
+            }
+        };
+        (len, Some(len))
     }
 }
 
+impl<'a, 'view> DoubleEndedIterator for RlpIterator<'a, 'view> {
+    fn next_back(&mut self) -> Option<Rlp<'a>> {
+        self.indexed().pop_back()
+    }
+}
+
+impl<'a, 'view> ExactSizeIterator for RlpIterator<'a, 'view> {}
+
+impl<'a, 'view> FusedIterator for RlpIterator<'a, 'view> {}
+
 pub struct BasicDecoder<'a> {
     rlp: Rlp<'a>,
 }
@@ -496,7 +747,7 @@ mod tests {
 
     #[test]
     fn at_overflow() {
-        let bs = vec![vec![1], vec![2, 3, 4], vec![3]].rlp_bytes();
+        let bs = vec![vec![1u8], vec![2, 3, 4], vec![3]].rlp_bytes();
         let rlp = Rlp::new(&*bs);
         let first_element: Result<Vec<u8>, _> = rlp.at(2).and_then(|elem| elem.as_val());
         assert_eq!(Ok(vec![3]), first_element);
@@ -0,0 +1,413 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::impls::integer_bytes;
+use super::{DecoderError, Rlp};
+use primitives::{H128, H160, H256, H512, H520, U256};
+use std::error::Error as StdError;
+use std::str::FromStr;
+use std::{cmp, fmt, mem};
+
+/// Expected shape of an RLP item.
+///
+/// The text form is the one printed by `Display`, e.g. `list of [bytes32, uint, list of bytes20]`.
+///
+/// ```rust
+/// use rlp::*;
+///
+/// fn main () {
+///     let schema: Schema = "[string, list of uint8]".parse().unwrap();
+///     let mut stream = RlpStream::new_list(2);
+///     stream.append(&"cat").begin_list(2).append(&1u8).append(&300u16);
+///     let mismatches = schema.validate(&Rlp::new(&stream.out())).unwrap_err();
+///     assert_eq!(mismatches[0].path, vec![1, 1]);
+///     assert_eq!(mismatches[0].error, DecoderError::IntegerOverflow { type_name: "uint", len: 2 });
+/// }
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Schema {
+    /// Any item.
+    Any,
+    /// Data decodable as `bool`.
+    Bool,
+    /// Unsigned integer of at most the given number of bytes, without leading zeros.
+    Uint(usize),
+    /// Data of any length.
+    Bytes,
+    /// Data of exactly the given number of bytes.
+    FixedBytes(usize),
+    /// Data decodable as `String`.
+    String,
+    /// List with exactly the given items.
+    List(Vec<Schema>),
+    /// List with any number of items of the same shape.
+    ListOf(Box<Schema>),
+    /// List with zero or one item, which is how `Option` is encoded.
+    Optional(Box<Schema>),
+}
+
+/// An item that does not match its `Schema`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SchemaMismatch {
+    /// Indexes of the item from the root.
+    pub path: Vec<usize>,
+    pub error: DecoderError,
+}
+
+impl fmt::Display for SchemaMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}: {}", self.path, self.error)
+    }
+}
+
+impl Schema {
+    /// Check whether the whole rlp has the expected shape.
+    pub fn validate(&self, rlp: &Rlp<'_>) -> Result<(), Vec<SchemaMismatch>> {
+        let mut mismatches = Vec::new();
+        self.validate_item(&mut Vec::new(), rlp, &mut mismatches);
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(mismatches)
+        }
+    }
+
+    fn validate_item(&self, path: &mut Vec<usize>, rlp: &Rlp<'_>, mismatches: &mut Vec<SchemaMismatch>) {
+        let result = match self {
+            Schema::List(items) => self.validate_items(path, rlp, mismatches, |index| items.get(index)),
+            Schema::ListOf(item) => self.validate_items(path, rlp, mismatches, |_| Some(item)),
+            Schema::Optional(item) => self.validate_items(path, rlp, mismatches, |index| {
+                if index == 0 {
+                    Some(item)
+                } else {
+                    None
+                }
+            }),
+            _ => self.validate_data(rlp),
+        };
+        if let Err(error) = result {
+            mismatches.push(SchemaMismatch {
+                path: path.clone(),
+                error,
+            });
+        }
+    }
+
+    fn validate_items<'s, F>(
+        &self,
+        path: &mut Vec<usize>,
+        rlp: &Rlp<'_>,
+        mismatches: &mut Vec<SchemaMismatch>,
+        schema_at: F,
+    ) -> Result<(), DecoderError>
+    where
+        F: Fn(usize) -> Option<&'s Schema>, {
+        let item_count = rlp.strict_item_count()?;
+        for index in 0..item_count {
+            let schema = match schema_at(index) {
+                Some(schema) => schema,
+                None => break,
+            };
+            path.push(index);
+            schema.validate_item(path, &rlp.at(index)?, mismatches);
+            path.pop();
+        }
+        let expected = match self {
+            Schema::List(items) => items.len(),
+            Schema::Optional(_) => cmp::min(item_count, 1),
+            _ => item_count,
+        };
+        if item_count != expected {
+            return Err(DecoderError::RlpIncorrectListLen {
+                expected,
+                got: item_count,
+            })
+        }
+        Ok(())
+    }
+
+    fn validate_data(&self, rlp: &Rlp<'_>) -> Result<(), DecoderError> {
+        match self {
+            Schema::Any => Ok(()),
+            Schema::Bool => rlp.as_val::<bool>().map(|_| ()),
+            Schema::String => rlp.as_val::<String>().map(|_| ()),
+            Schema::Bytes => rlp.decoder().decode_value(|_| Ok(())),
+            Schema::FixedBytes(size) => rlp.decoder().decode_value(|bytes| match bytes.len().cmp(size) {
+                cmp::Ordering::Less => Err(DecoderError::RlpIsTooShort {
+                    expected: *size,
+                    got: bytes.len(),
+                }),
+                cmp::Ordering::Greater => Err(DecoderError::RlpIsTooBig {
+                    expected: *size,
+                    got: bytes.len(),
+                }),
+                cmp::Ordering::Equal => Ok(()),
+            }),
+            Schema::Uint(size) => {
+                let profile = rlp.profile();
+                rlp.decoder().decode_value(|bytes| integer_bytes(profile, bytes, "uint", *size).map(|_| ()))
+            }
+            Schema::List(_) | Schema::ListOf(_) | Schema::Optional(_) => unreachable!("lists are validated by items"),
+        }
+    }
+}
+
+impl fmt::Display for Schema {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Schema::Any => write!(f, "any"),
+            Schema::Bool => write!(f, "bool"),
+            Schema::Uint(size) => write!(f, "uint{}", size * 8),
+            Schema::Bytes => write!(f, "bytes"),
+            Schema::FixedBytes(size) => write!(f, "bytes{}", size),
+            Schema::String => write!(f, "string"),
+            Schema::List(items) => {
+                write!(f, "[")?;
+                for (index, item) in items.iter().enumerate() {
+                    if index != 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            Schema::ListOf(item) => write!(f, "list of {}", item),
+            Schema::Optional(item) => write!(f, "optional {}", item),
+        }
+    }
+}
+
+/// Error returned when the text form of a `Schema` is malformed.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseSchemaError {
+    /// Byte offset in the text where the error was found.
+    pub position: usize,
+    pub reason: &'static str,
+}
+
+impl StdError for ParseSchemaError {}
+
+impl fmt::Display for ParseSchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at {}", self.reason, self.position)
+    }
+}
+
+impl FromStr for Schema {
+    type Err = ParseSchemaError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = SchemaParser {
+            text: s,
+            position: 0,
+        };
+        let schema = parser.parse()?;
+        parser.skip_whitespace();
+        if parser.position != s.len() {
+            return Err(parser.error(parser.position, "Unexpected trailing characters"))
+        }
+        Ok(schema)
+    }
+}
+
+struct SchemaParser<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> SchemaParser<'a> {
+    fn error(&self, position: usize, reason: &'static str) -> ParseSchemaError {
+        ParseSchemaError {
+            position,
+            reason,
+        }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.text[self.position..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn word(&mut self) -> &'a str {
+        let rest = self.rest();
+        let len = rest.find(|c: char| !c.is_ascii_alphanumeric()).unwrap_or(rest.len());
+        self.position += len;
+        &rest[..len]
+    }
+
+    fn parse(&mut self) -> Result<Schema, ParseSchemaError> {
+        self.skip_whitespace();
+        if self.rest().starts_with('[') {
+            self.position += 1;
+            return self.parse_list()
+        }
+
+        let start = self.position;
+        let word = self.word();
+        let schema = match word {
+            "" => return Err(self.error(start, "Expected a schema")),
+            "any" => Schema::Any,
+            "bool" => Schema::Bool,
+            "uint" => Schema::Uint(32),
+            "bytes" => Schema::Bytes,
+            "string" => Schema::String,
+            "list" => {
+                self.skip_whitespace();
+                let of = self.position;
+                if self.word() != "of" {
+                    return Err(self.error(of, "Expected 'of'"))
+                }
+                Schema::ListOf(Box::new(self.parse()?))
+            }
+            "optional" => Schema::Optional(Box::new(self.parse()?)),
+            _ if word.starts_with("uint") => match word["uint".len()..].parse::<usize>() {
+                Ok(bits) if bits != 0 && bits % 8 == 0 => Schema::Uint(bits / 8),
+                _ => return Err(self.error(start, "Invalid number of bits")),
+            },
+            _ if word.starts_with("bytes") => match word["bytes".len()..].parse::<usize>() {
+                Ok(size) if size != 0 => Schema::FixedBytes(size),
+                _ => return Err(self.error(start, "Invalid number of bytes")),
+            },
+            _ => return Err(self.error(start, "Unknown schema")),
+        };
+        Ok(schema)
+    }
+
+    fn parse_list(&mut self) -> Result<Schema, ParseSchemaError> {
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.rest().starts_with(']') {
+            self.position += 1;
+            return Ok(Schema::List(items))
+        }
+        loop {
+            items.push(self.parse()?);
+            self.skip_whitespace();
+            match self.rest().chars().next() {
+                Some(',') => self.position += 1,
+                Some(']') => {
+                    self.position += 1;
+                    return Ok(Schema::List(items))
+                }
+                _ => return Err(self.error(self.position, "Expected ',' or ']'")),
+            }
+        }
+    }
+}
+
+/// Type whose RLP shape is known, usually derived with `RlpSchema`.
+pub trait RlpSchema {
+    /// The shape of the RLP decoded by this type.
+    fn rlp_schema() -> Schema;
+
+    /// The shape of a `Vec` of this type, which is a list of it unless the type overrides it.
+    #[doc(hidden)]
+    fn rlp_schema_vec() -> Schema
+    where
+        Self: Sized, {
+        Schema::ListOf(Box::new(Self::rlp_schema()))
+    }
+}
+
+impl RlpSchema for bool {
+    fn rlp_schema() -> Schema {
+        Schema::Bool
+    }
+}
+
+macro_rules! impl_schema_for_uint {
+    ($name: ident) => {
+        impl RlpSchema for $name {
+            fn rlp_schema() -> Schema {
+                Schema::Uint(mem::size_of::<$name>())
+            }
+        }
+    };
+}
+
+impl RlpSchema for u8 {
+    fn rlp_schema() -> Schema {
+        Schema::Uint(1)
+    }
+
+    fn rlp_schema_vec() -> Schema {
+        Schema::Bytes
+    }
+}
+
+impl_schema_for_uint!(u16);
+impl_schema_for_uint!(u32);
+impl_schema_for_uint!(u64);
+impl_schema_for_uint!(u128);
+impl_schema_for_uint!(U256);
+
+impl RlpSchema for usize {
+    fn rlp_schema() -> Schema {
+        u64::rlp_schema()
+    }
+}
+
+macro_rules! impl_schema_for_hash {
+    ($name: ident, $size: expr) => {
+        impl RlpSchema for $name {
+            fn rlp_schema() -> Schema {
+                Schema::FixedBytes($size)
+            }
+        }
+    };
+}
+
+impl_schema_for_hash!(H128, 16);
+impl_schema_for_hash!(H160, 20);
+impl_schema_for_hash!(H256, 32);
+impl_schema_for_hash!(H512, 64);
+impl_schema_for_hash!(H520, 65);
+
+macro_rules! impl_schema_for_byte_array {
+    ($($size: expr),*) => {
+        $(
+            impl RlpSchema for [u8; $size] {
+                fn rlp_schema() -> Schema {
+                    Schema::FixedBytes($size)
+                }
+            }
+        )*
+    };
+}
+
+// The same sizes as the `Encodable` and `Decodable` implementations.
+impl_schema_for_byte_array!(1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16);
+impl_schema_for_byte_array!(17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 64, 65);
+
+impl<T: RlpSchema> RlpSchema for Vec<T> {
+    fn rlp_schema() -> Schema {
+        T::rlp_schema_vec()
+    }
+}
+
+impl RlpSchema for String {
+    fn rlp_schema() -> Schema {
+        Schema::String
+    }
+}
+
+impl<T: RlpSchema> RlpSchema for Option<T> {
+    fn rlp_schema() -> Schema {
+        Schema::Optional(Box::new(T::rlp_schema()))
+    }
+}
+
+impl<T1: RlpSchema, T2: RlpSchema, T3: RlpSchema> RlpSchema for (T1, T2, T3) {
+    fn rlp_schema() -> Schema {
+        Schema::List(vec![T1::rlp_schema(), T2::rlp_schema(), T3::rlp_schema()])
+    }
+}
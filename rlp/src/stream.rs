@@ -227,8 +227,9 @@ impl RlpStream {
     pub fn estimate_size(&self, add: usize) -> usize {
         let total_size = self.buffer.len() + add;
         let mut base_size = total_size;
-        for list in &self.unfinished_lists[..] {
-            let len = total_size - list.position;
+        // the length bytes of an inner list also belong to the payloads of the lists enclosing it
+        for list in self.unfinished_lists.iter().rev() {
+            let len = base_size - list.position;
             if len > 55 {
                 let leading_empty_bytes = (len as u64).leading_zeros() as usize / 8;
                 let size_bytes = 8 - leading_empty_bytes;
@@ -358,14 +359,14 @@ impl<'a> BasicEncoder<'a> {
     }
 
     fn insert_size(&mut self, size: usize, position: usize) -> u8 {
-        let size = size as u32;
+        let size = size as u64;
         let leading_empty_bytes = size.leading_zeros() as usize / 8;
-        let size_bytes = 4 - leading_empty_bytes as u8;
-        let buffer: [u8; 4] = size.to_be_bytes();
+        let size_bytes = 8 - leading_empty_bytes;
+        let buffer: [u8; 8] = size.to_be_bytes();
 
         assert!(position <= self.buffer.len());
         self.buffer.extend_from_slice(&buffer[leading_empty_bytes..]);
-        self.buffer[position..].rotate_right(size_bytes as usize);
+        self.buffer[position..].rotate_right(size_bytes);
 
         size_bytes as u8
     }
@@ -432,6 +433,37 @@ impl<'a> BasicEncoder<'a> {
 mod tests {
     use super::*;
 
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn insert_size_beyond_u32() {
+        // Only the length bytes are written, so the boundary can be checked without a 4 GiB payload.
+        let cases: [(usize, &[u8]); 5] = [
+            (0xffff_ffff, &[0xff, 0xff, 0xff, 0xff]),
+            (0x1_0000_0000, &[0x01, 0x00, 0x00, 0x00, 0x00]),
+            (0x0123_4567_89ab, &[0x01, 0x23, 0x45, 0x67, 0x89, 0xab]),
+            (0x00ff_ffff_ffff_ffff, &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]),
+            (usize::MAX, &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]),
+        ];
+        for (size, expected) in cases.iter() {
+            let mut stream = RlpStream::new();
+            stream.buffer.extend_from_slice(&[0xaa, 0xbb]);
+            let inserted_bytes = stream.encoder().insert_size(*size, 1);
+            assert_eq!(inserted_bytes as usize, expected.len());
+            assert_eq!(stream.buffer[0], 0xaa);
+            assert_eq!(&stream.buffer[1..=expected.len()], *expected);
+            assert_eq!(stream.buffer[expected.len() + 1], 0xbb);
+        }
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn insert_list_payload_beyond_u32() {
+        let mut stream = RlpStream::new();
+        stream.buffer.push(0);
+        stream.encoder().insert_list_payload(0x1_0000_0000, 1);
+        assert_eq!(stream.buffer, vec![0xf7 + 5, 0x01, 0x00, 0x00, 0x00, 0x00]);
+    }
+
     #[test]
     #[ignore]
     fn append_more_than_expected() {
@@ -14,6 +14,14 @@ use super::{DecoderError, Rlp, RlpStream};
 pub trait Decodable: Sized {
     /// Decode a value from RLP bytes
     fn decode(rlp: &Rlp) -> Result<Self, DecoderError>;
+
+    /// Decode a `Vec` of values, which is a list of them unless the type overrides it.
+    ///
+    /// `u8` overrides it to decode `Vec<u8>` from data.
+    #[doc(hidden)]
+    fn decode_vec(rlp: &Rlp) -> Result<Vec<Self>, DecoderError> {
+        rlp.as_list()
+    }
 }
 
 /// Structure encodable to RLP
@@ -27,4 +35,17 @@ pub trait Encodable {
         self.rlp_append(&mut s);
         s.drain()
     }
+
+    /// Append a slice of values, which is a list of them unless the type overrides it.
+    ///
+    /// `u8` overrides it to encode `Vec<u8>` and `&[u8]` as data.
+    #[doc(hidden)]
+    fn rlp_append_slice(values: &[Self], s: &mut RlpStream)
+    where
+        Self: Sized, {
+        s.begin_list(values.len());
+        for value in values {
+            s.append(value);
+        }
+    }
 }
@@ -0,0 +1,112 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use rlp::{CanonicalFix, CanonicalFixKind, DecoderError, PayloadInfo, Rlp, RlpStream};
+
+fn fix(path: &[usize], kind: CanonicalFixKind) -> CanonicalFix {
+    CanonicalFix {
+        path: path.to_vec(),
+        kind,
+    }
+}
+
+#[test]
+fn canonical_input_is_unchanged() {
+    let mut stream = RlpStream::new_list(3);
+    stream.append(&"cat").append(&vec![0u8; 100]).begin_list(1).append(&5u8);
+    let data = stream.out();
+    assert_eq!(rlp::canonicalize(&data), Ok((data, vec![])));
+}
+
+#[test]
+fn single_byte_indirection() {
+    assert_eq!(
+        rlp::canonicalize(&[0x81, 0x05]),
+        Ok((vec![0x05], vec![fix(&[], CanonicalFixKind::SingleByteIndirection)]))
+    );
+    // not a violation for bytes which need the header
+    assert_eq!(rlp::canonicalize(&[0x81, 0x80]), Ok((vec![0x81, 0x80], vec![])));
+}
+
+#[test]
+fn long_form_header_for_short_string() {
+    let legacy = [0xc0 + 4, 0xb7 + 1, 2, b'a', b'b'];
+    assert_eq!(Rlp::new(&legacy).val_at::<String>(0), Err(DecoderError::RlpInvalidIndirection));
+
+    let (canonical, fixes) = rlp::canonicalize(&legacy).unwrap();
+    assert_eq!(canonical, vec![0xc3, 0x82, b'a', b'b']);
+    assert_eq!(fixes, vec![fix(&[0], CanonicalFixKind::LongFormHeader)]);
+    assert_eq!(Rlp::new(&canonical).val_at::<String>(0), Ok("ab".to_owned()));
+}
+
+#[test]
+fn long_form_header_for_short_list() {
+    let legacy = [0xf7 + 1, 4, 0xc0 + 3, 0x82, b'a', b'b'];
+    assert_eq!(
+        rlp::canonicalize(&legacy),
+        Ok((vec![0xc4, 0xc3, 0x82, b'a', b'b'], vec![fix(&[], CanonicalFixKind::LongFormHeader)]))
+    );
+}
+
+#[test]
+fn length_with_zero_prefix() {
+    let mut legacy = vec![0xb7 + 3, 0, 0, 60];
+    legacy.extend_from_slice(&[b'x'; 60]);
+    let (canonical, fixes) = rlp::canonicalize(&legacy).unwrap();
+    assert_eq!(canonical, rlp::encode(&vec![b'x'; 60]));
+    assert_eq!(fixes, vec![fix(&[], CanonicalFixKind::LengthWithZeroPrefix)]);
+}
+
+#[test]
+fn several_fixes_in_one_item() {
+    // a single byte in a long form header with a zero prefixed length
+    let legacy = [0xc0 + 5, 0xb7 + 2, 0, 1, 0x05, 0x06];
+    let (canonical, fixes) = rlp::canonicalize(&legacy).unwrap();
+    assert_eq!(canonical, vec![0xc2, 0x05, 0x06]);
+    assert_eq!(fixes, vec![
+        fix(&[0], CanonicalFixKind::LengthWithZeroPrefix),
+        fix(&[0], CanonicalFixKind::LongFormHeader),
+        fix(&[0], CanonicalFixKind::SingleByteIndirection),
+    ]);
+}
+
+#[test]
+fn truncated_input() {
+    assert_eq!(
+        rlp::canonicalize(&[0xc3, 0x82, b'a']),
+        Err(DecoderError::RlpIsTooShort {
+            expected: 4,
+            got: 3
+        })
+    );
+    assert_eq!(
+        rlp::canonicalize(&[0xc2, 0x82, b'a']),
+        Err(DecoderError::RlpIsTooShort {
+            expected: 3,
+            got: 2
+        })
+    );
+}
+
+#[test]
+fn trailing_bytes() {
+    assert_eq!(
+        rlp::canonicalize(&[0x82, b'a', b'b', b'c']),
+        Err(DecoderError::RlpIsTooBig {
+            expected: 3,
+            got: 4
+        })
+    );
+}
+
+#[test]
+fn lenient_payload_info() {
+    let (info, fixes) = PayloadInfo::from_lenient(&[0xb7 + 2, 0, 3, b'c', b'a', b't']).unwrap();
+    assert_eq!((info.header_len, info.value_len), (3, 3));
+    assert_eq!(fixes, vec![CanonicalFixKind::LengthWithZeroPrefix, CanonicalFixKind::LongFormHeader]);
+}
@@ -0,0 +1,104 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(feature = "tokio")]
+
+use futures::{SinkExt, StreamExt};
+use rlp::{CodecError, DecoderError, RlpCodec};
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_util::codec::{FramedRead, FramedWrite};
+
+#[tokio::test]
+async fn frames_over_loopback() {
+    let mut listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let sender = tokio::spawn(async move {
+        let stream = TcpStream::connect(addr).await.unwrap();
+        let mut frames = FramedWrite::new(stream, RlpCodec::new());
+        frames.send(0x7fu8).await.unwrap();
+        frames.send("cat").await.unwrap();
+        frames.send((1u64, 2u64, 3u64)).await.unwrap();
+        frames.send(vec![0xab_u8; 1024]).await.unwrap();
+    });
+
+    let (stream, _) = listener.accept().await.unwrap();
+    let frames: Vec<_> = FramedRead::new(stream, RlpCodec::new()).map(|frame| frame.unwrap()).collect().await;
+    sender.await.unwrap();
+
+    assert_eq!(frames.len(), 4);
+    assert_eq!(rlp::decode::<u8>(&frames[0]), Ok(0x7f));
+    assert_eq!(rlp::decode::<String>(&frames[1]), Ok("cat".to_string()));
+    assert_eq!(rlp::decode::<(u64, u64, u64)>(&frames[2]), Ok((1, 2, 3)));
+    assert_eq!(rlp::decode::<Vec<u8>>(&frames[3]), Ok(vec![0xab; 1024]));
+}
+
+#[tokio::test]
+async fn frames_split_across_writes() {
+    let mut listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let encoded = rlp::encode_list::<u64, _>(&[1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    let expected = encoded.clone();
+
+    let sender = tokio::spawn(async move {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        for chunk in encoded.chunks(2) {
+            stream.write_all(chunk).await.unwrap();
+            stream.flush().await.unwrap();
+        }
+    });
+
+    let (stream, _) = listener.accept().await.unwrap();
+    let mut frames = FramedRead::new(stream, RlpCodec::new());
+    assert_eq!(frames.next().await.unwrap().unwrap(), &expected[..]);
+    assert!(frames.next().await.is_none());
+    sender.await.unwrap();
+}
+
+#[tokio::test]
+async fn reject_frame_too_big() {
+    let bytes = rlp::encode(&vec![0u8; 100]);
+    let mut frames = FramedRead::new(&bytes[..], RlpCodec::with_max_frame_size(64));
+    match frames.next().await {
+        Some(Err(CodecError::FrameTooBig {
+            max: 64,
+            got: 102,
+        })) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let mut frames = FramedWrite::new(Vec::new(), RlpCodec::with_max_frame_size(64));
+    match frames.send(vec![0u8; 100]).await {
+        Err(CodecError::FrameTooBig {
+            max: 64,
+            got: 102,
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn reject_invalid_header() {
+    let bytes = [0xb8, 0x05, 1, 2, 3, 4, 5];
+    let mut frames = FramedRead::new(&bytes[..], RlpCodec::new());
+    match frames.next().await {
+        Some(Err(CodecError::Decoder(DecoderError::RlpInvalidIndirection))) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn reject_truncated_stream() {
+    let bytes = [0x83, b'c', b'a'];
+    let mut frames = FramedRead::new(&bytes[..], RlpCodec::new());
+    match frames.next().await {
+        Some(Err(CodecError::Io(_))) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
@@ -0,0 +1,110 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use rlp::{DecoderError, Rlp, RlpStream};
+
+fn header() -> Vec<u8> {
+    let mut stream = RlpStream::new_list(3);
+    stream.append(&"cat").append(&7u64).append_list::<u64, _>(&[1, 2, 3]);
+    stream.out()
+}
+
+#[test]
+fn read_items_in_order() {
+    let data = header();
+    let rlp = Rlp::new(&data);
+    let mut cursor = rlp.cursor().unwrap();
+    assert_eq!(cursor.remaining(), 3);
+    assert_eq!(cursor.next::<String>(), Ok("cat".to_string()));
+    assert_eq!(cursor.remaining(), 2);
+    assert_eq!(cursor.next::<u64>(), Ok(7));
+    assert_eq!(cursor.next_list::<u64>(), Ok(vec![1, 2, 3]));
+    assert_eq!(cursor.remaining(), 0);
+    assert_eq!(cursor.finish(), Ok(()));
+}
+
+#[test]
+fn peek_does_not_consume() {
+    let data = header();
+    let rlp = Rlp::new(&data);
+    let mut cursor = rlp.cursor().unwrap();
+    assert_eq!(cursor.peek().unwrap().as_raw(), &[0x83, b'c', b'a', b't']);
+    assert_eq!(cursor.peek().unwrap().as_raw(), &[0x83, b'c', b'a', b't']);
+    assert_eq!(cursor.next::<String>(), Ok("cat".to_string()));
+    assert!(cursor.peek().unwrap().is_int());
+
+    cursor.next::<u64>().unwrap();
+    cursor.next_list::<u64>().unwrap();
+    assert!(cursor.peek().is_none());
+}
+
+#[test]
+fn finish_rejects_unconsumed_items() {
+    let data = header();
+    let rlp = Rlp::new(&data);
+    let mut cursor = rlp.cursor().unwrap();
+    cursor.next::<String>().unwrap();
+    assert_eq!(
+        cursor.finish(),
+        Err(DecoderError::RlpIncorrectListLen {
+            expected: 1,
+            got: 3,
+        })
+    );
+}
+
+#[test]
+fn next_beyond_the_end() {
+    let data = [0xc1, 0x01];
+    let rlp = Rlp::new(&data);
+    let mut cursor = rlp.cursor().unwrap();
+    assert_eq!(cursor.next::<u8>(), Ok(1));
+    assert_eq!(
+        cursor.next::<u8>(),
+        Err(DecoderError::RlpIncorrectListLen {
+            expected: 2,
+            got: 1,
+        })
+    );
+}
+
+#[test]
+fn malformed_items() {
+    // The declared payload ends in the middle of the second item.
+    let data = [0xc3, 0x01, 0x83, b'c', b'a', b't'];
+    let rlp = Rlp::new(&data);
+    let mut cursor = rlp.cursor().unwrap();
+    assert_eq!(cursor.next::<u8>(), Ok(1));
+    assert_eq!(cursor.remaining(), 0);
+    assert!(cursor.peek().is_none());
+    assert_eq!(
+        cursor.clone().finish(),
+        Err(DecoderError::RlpIsTooShort {
+            expected: 4,
+            got: 2,
+        })
+    );
+    assert_eq!(
+        cursor.next::<String>(),
+        Err(DecoderError::RlpIsTooShort {
+            expected: 4,
+            got: 2,
+        })
+    );
+
+    let data = [0xc2, 0xc0, 0x01];
+    let rlp = Rlp::new(&data);
+    let mut cursor = rlp.cursor().unwrap();
+    assert_eq!(cursor.next::<u8>(), Err(DecoderError::RlpExpectedToBeData));
+}
+
+#[test]
+fn cursor_of_data() {
+    let data = [0x83, b'c', b'a', b't'];
+    assert_eq!(Rlp::new(&data).cursor().err(), Some(DecoderError::RlpExpectedToBeList));
+}
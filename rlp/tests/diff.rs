@@ -0,0 +1,97 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use rlp::{DecoderError, DifferenceKind, RlpStream};
+
+fn block(gas_limit: u64, uncles: &[&str], extra: &[u8]) -> Vec<u8> {
+    let mut stream = RlpStream::new_list(3);
+    stream.begin_list(2).append(&gas_limit).append(&"miner");
+    stream.append_list::<&str, _>(uncles);
+    stream.append(&extra);
+    stream.out()
+}
+
+#[test]
+fn identical() {
+    let a = block(1000, &["a", "b"], &[]);
+    assert_eq!(rlp::diff(&a, &a).unwrap().len(), 0);
+}
+
+#[test]
+fn changed_nested_data() {
+    let a = block(1000, &["a", "b"], &[]);
+    let b = block(2000, &["a", "b"], &[]);
+    let differences = rlp::diff(&a, &b).unwrap();
+    assert_eq!(differences.len(), 1);
+    assert_eq!(differences[0].path, vec![0, 0]);
+    assert_eq!(differences[0].kind, DifferenceKind::ChangedData);
+    assert_eq!(differences[0].left.as_val::<u64>(), Ok(1000));
+    assert_eq!(differences[0].right.as_val::<u64>(), Ok(2000));
+    assert_eq!(differences[0].to_string(), "[0, 0]: changed data, \"0x03e8\" != \"0x07d0\"");
+}
+
+#[test]
+fn length_mismatch_compares_common_items() {
+    let a = block(1000, &["a", "b"], &[]);
+    let b = block(1000, &["c"], &[]);
+    let differences = rlp::diff(&a, &b).unwrap();
+    let summary: Vec<_> = differences.iter().map(|d| (d.path.clone(), d.kind)).collect();
+    assert_eq!(summary, vec![(vec![1], DifferenceKind::LengthMismatch), (vec![1, 0], DifferenceKind::ChangedData)]);
+    assert_eq!(differences[0].to_string(), "[1]: length mismatch, [\"0x61\", \"0x62\"] != [\"0x63\"]");
+}
+
+#[test]
+fn type_mismatch() {
+    let a = block(1000, &[], &[]);
+    let mut stream = RlpStream::new_list(3);
+    stream.append(&"header");
+    stream.begin_list(0);
+    stream.append(&"");
+    let b = stream.out();
+    let differences = rlp::diff(&a, &b).unwrap();
+    assert_eq!(differences.len(), 1);
+    assert_eq!(differences[0].path, vec![0]);
+    assert_eq!(differences[0].kind, DifferenceKind::TypeMismatch);
+}
+
+#[test]
+fn non_canonical_data_is_equal() {
+    // "a" encoded with an unnecessary header still carries the same data
+    let a = vec![0xc1, b'a'];
+    let b = vec![0xc2, 0x81, b'a'];
+    assert_eq!(rlp::diff(&a, &b).unwrap().len(), 0);
+}
+
+#[test]
+fn malformed_input() {
+    let a = vec![0xc8, 0x83, b'c', b'a', b't', 0x83, b'd', b'o', b'g'];
+    let b = vec![0xc8, 0x83, b'c', b'a', b't', 0x85, b'd', b'o', b'g'];
+    assert_eq!(
+        rlp::diff(&a, &b).map(|differences| differences.len()),
+        Err(DecoderError::RlpIsTooShort {
+            expected: 1,
+            got: 0
+        })
+    );
+}
+
+#[test]
+fn truncated_item_is_an_error() {
+    assert_eq!(
+        rlp::diff(&[0xa0, 0x86], &[0xc0]).unwrap_err(),
+        DecoderError::RlpIsTooShort {
+            expected: 1,
+            got: 0,
+        }
+    );
+    assert!(rlp::diff(&[0xc2, 0xc0, 0x81], &[0xc1, 0xc0]).is_err());
+
+    let differences = rlp::diff(&[0xc2, 0x05, 0xc0], &[0xc1, 0xc0]).unwrap();
+    let rendered: Vec<_> = differences.iter().map(ToString::to_string).collect();
+    assert_eq!(rendered, vec!["[]: length mismatch, [\"0x05\", []] != [[]]", "[0]: type mismatch, \"0x05\" != []"]);
+}
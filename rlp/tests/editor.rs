@@ -0,0 +1,114 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use primitives::H256;
+use rlp::{DecoderError, Rlp, RlpEditor, RlpStream};
+
+fn header(state_root: H256, extra: &[u8]) -> Vec<u8> {
+    let mut stream = RlpStream::new_list(4);
+    stream.append(&H256::zero()).append(&state_root).append(&1_000_000u64).append(&extra);
+    stream.out()
+}
+
+#[test]
+fn replace_nested_field() {
+    let original = header(H256::zero(), &[0xab; 100]);
+    let expected = header(H256::repeat_byte(0x11), &[0xab; 100]);
+
+    let mut editor = RlpEditor::new(Rlp::new(&original));
+    editor.replace(&[1], &H256::repeat_byte(0x11));
+    assert_eq!(editor.out(), Ok(expected));
+}
+
+#[test]
+fn untouched_items_are_copied_verbatim() {
+    // The first item is a non-canonical encoding of "a" that must survive the edit untouched.
+    let data = vec![0xc5, 0x81, b'a', 0x82, b'b', b'c'];
+    let mut editor = RlpEditor::new(Rlp::new(&data));
+    editor.replace(&[1], &"d");
+    assert_eq!(editor.out(), Ok(vec![0xc3, 0x81, b'a', b'd']));
+}
+
+#[test]
+fn no_edits_copies_input() {
+    let data = vec![0xc8, 0x83, b'c', b'a', b't', 0x83, b'd', b'o', b'g'];
+    assert_eq!(RlpEditor::new(Rlp::new(&data)).out(), Ok(data));
+}
+
+#[test]
+fn insert_remove_and_append() {
+    let data = rlp::encode_list::<u64, _>(&[1, 2, 3]);
+    let mut editor = RlpEditor::new(Rlp::new(&data));
+    editor.insert(&[0], &0u64).remove(&[1]).insert(&[3], &4u64).append(&[], &5u64);
+    assert_eq!(editor.out().and_then(|out| rlp::decode_list::<u64>(&out)), Ok(vec![0, 1, 3, 4, 5]));
+}
+
+#[test]
+fn edit_grows_header_of_ancestors() {
+    // [[ "cat" ]] becomes [[ "cat", <60 bytes> ]], so both lists need a long header.
+    let data = vec![0xc5, 0xc4, 0x83, b'c', b'a', b't'];
+    let mut editor = RlpEditor::new(Rlp::new(&data));
+    editor.append(&[0], &vec![0u8; 60]);
+
+    let expected = {
+        let mut stream = RlpStream::new_list(1);
+        stream.begin_list(2).append(&"cat").append(&vec![0u8; 60]);
+        stream.out()
+    };
+    assert_eq!(editor.out(), Ok(expected));
+}
+
+#[test]
+fn replace_root() {
+    let data = vec![0x83, b'c', b'a', b't'];
+    let mut editor = RlpEditor::new(Rlp::new(&data));
+    editor.replace(&[], &"dog");
+    assert_eq!(editor.out(), Ok(vec![0x83, b'd', b'o', b'g']));
+}
+
+#[test]
+fn out_of_range() {
+    let data = vec![0xc8, 0x83, b'c', b'a', b't', 0x83, b'd', b'o', b'g'];
+    let mut editor = RlpEditor::new(Rlp::new(&data));
+    editor.remove(&[2]);
+    assert_eq!(
+        editor.out(),
+        Err(DecoderError::RlpIsTooShort {
+            expected: 3,
+            got: 2
+        })
+    );
+}
+
+#[test]
+fn edit_inside_data() {
+    let data = vec![0xc8, 0x83, b'c', b'a', b't', 0x83, b'd', b'o', b'g'];
+    let mut editor = RlpEditor::new(Rlp::new(&data));
+    editor.replace(&[0, 0], &"cow");
+    assert_eq!(editor.out(), Err(DecoderError::RlpExpectedToBeList));
+}
+
+#[test]
+fn conflicting_edits() {
+    let data = vec![0xc9, 0x83, b'c', b'a', b't', 0xc4, 0x83, b'd', b'o', b'g'];
+    let mut editor = RlpEditor::new(Rlp::new(&data));
+    editor.remove(&[1]).replace(&[1, 0], &"cow");
+    assert_eq!(editor.out(), Err(DecoderError::Custom("Conflicting edits on the same item".to_string())));
+}
+
+#[test]
+fn items_overrunning_list_payload() {
+    // The list declares 4 bytes of payload, but its second item ends 1 byte past them.
+    let data = vec![0xc4, 0x81, b'a', 0x82, b'b', b'c'];
+    let mut editor = RlpEditor::new(Rlp::new(&data));
+    editor.replace(&[0], &"d");
+    assert_eq!(editor.out(), Err(DecoderError::RlpIsTooShort {
+        expected: 1,
+        got: 0,
+    }));
+}
@@ -0,0 +1,113 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use primitives::U256;
+use rlp::{Decodable, DecoderError, DecoderResultExt, Rlp};
+use std::error::Error;
+
+struct Header {
+    number: u64,
+    gas_limit: u64,
+}
+
+impl Decodable for Header {
+    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        Ok(Header {
+            number: rlp.val_at(0).with_context("number")?,
+            gas_limit: rlp.val_at(1).with_context("gas_limit")?,
+        })
+    }
+}
+
+#[test]
+fn messages() {
+    assert_eq!(
+        DecoderError::RlpIsTooBig {
+            expected: 8,
+            got: 9,
+        }
+        .to_string(),
+        "RLP is too big: expected 8 bytes, got 9"
+    );
+    assert_eq!(
+        DecoderError::RlpIncorrectListLen {
+            expected: 2,
+            got: 3,
+        }
+        .to_string(),
+        "RLP list has an incorrect length: expected 2 items, got 3"
+    );
+    assert_eq!(
+        rlp::decode::<u8>(&[0x82, 0x01]).unwrap_err().to_string(),
+        "RLP needs 3 bytes as declared, but only 2 bytes exist"
+    );
+    assert_eq!(rlp::decode::<bool>(&[0x02]).unwrap_err().to_string(), "RLP bool must be 0 or 1, got 2");
+    assert_eq!(DecoderError::custom("Invalid signature").to_string(), "Invalid signature");
+    assert_eq!(DecoderError::custom(format_args!("Unknown type {}", 7)), DecoderError::Custom("Unknown type 7".to_string()));
+}
+
+#[test]
+fn context_is_chained() {
+    let data = rlp::encode(&(1u64, vec![0u8; 9], 0u8));
+    let err = rlp::decode::<Header>(&data).with_context("header").err().unwrap();
+    assert_eq!(err.to_string(), "header: gas_limit: RLP integer of 9 bytes overflows u64");
+    assert_eq!(err.root_cause(), &DecoderError::IntegerOverflow {
+        type_name: "u64",
+        len: 9,
+    });
+
+    let source = err.source().unwrap();
+    assert_eq!(source.to_string(), "gas_limit: RLP integer of 9 bytes overflows u64");
+    let source = source.source().unwrap();
+    assert_eq!(source.to_string(), "RLP integer of 9 bytes overflows u64");
+    assert!(source.source().is_none());
+}
+
+#[test]
+fn context_is_not_added_to_success() {
+    let data = rlp::encode(&(1u64, 2u64, 0u8));
+    let header = rlp::decode::<Header>(&data).with_context("header").unwrap();
+    assert_eq!((header.number, header.gas_limit), (1, 2));
+}
+
+#[test]
+fn invalid_utf8() {
+    let data = rlp::encode(&vec![b'c', b'a', 0xff, b't']);
+    assert_eq!(
+        rlp::decode::<String>(&data),
+        Err(DecoderError::InvalidUtf8 {
+            valid_up_to: 2,
+        })
+    );
+}
+
+fn overflow<T>(type_name: &'static str, len: usize) -> Result<T, DecoderError> {
+    Err(DecoderError::IntegerOverflow {
+        type_name,
+        len,
+    })
+}
+
+#[test]
+fn integer_overflow() {
+    assert_eq!(rlp::decode::<bool>(&[0x82, 0x01, 0x00]), overflow("bool", 2));
+    assert_eq!(rlp::decode::<u8>(&[0x82, 0x01, 0x00]), overflow("u8", 2));
+    assert_eq!(rlp::decode::<u16>(&[0x83, 0x01, 0x00, 0x00]), overflow("u16", 3));
+    assert_eq!(rlp::decode::<u32>(&rlp::encode(&0x1_0000_0000u64)), overflow("u32", 5));
+    assert_eq!(rlp::decode::<u64>(&rlp::encode(&(1u128 << 64))), overflow("u64", 9));
+    assert_eq!(rlp::decode::<usize>(&rlp::encode(&(1u128 << 64))), overflow("usize", 9));
+    assert_eq!(rlp::decode::<U256>(&rlp::encode(&vec![0x01u8; 33])), overflow("U256", 33));
+}
+
+#[test]
+fn leading_zero_integer() {
+    assert_eq!(rlp::decode::<u8>(&[0x00]), Err(DecoderError::LeadingZeroInteger));
+    assert_eq!(rlp::decode::<u16>(&[0x00]), Err(DecoderError::LeadingZeroInteger));
+    assert_eq!(rlp::decode::<u64>(&[0x82, 0x00, 0x01]), Err(DecoderError::LeadingZeroInteger));
+    assert_eq!(rlp::decode::<U256>(&[0x82, 0x00, 0x01]), Err(DecoderError::LeadingZeroInteger));
+}
@@ -0,0 +1,135 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use rlp::{DecoderError, ReadError, RlpReader, RlpWriter};
+use std::io::{self, Read};
+
+/// Returns at most one byte per read, interrupting every other read.
+struct Trickle<'a> {
+    bytes: &'a [u8],
+    interrupt: bool,
+}
+
+impl<'a> Read for Trickle<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.interrupt = !self.interrupt;
+        if self.interrupt {
+            return Err(io::Error::new(io::ErrorKind::Interrupted, "interrupted"))
+        }
+        if self.bytes.is_empty() || buf.is_empty() {
+            return Ok(0)
+        }
+        buf[0] = self.bytes[0];
+        self.bytes = &self.bytes[1..];
+        Ok(1)
+    }
+}
+
+fn export() -> Vec<u8> {
+    let mut writer = RlpWriter::new(Vec::new());
+    writer.write(&"cat").unwrap();
+    writer.write(&0x0400u32).unwrap();
+    writer.write_raw(&[0xc2, 0x01, 0x02]).unwrap();
+    writer.write(&vec![0xab_u8; 1024]).unwrap();
+    writer.flush().unwrap();
+    writer.into_inner()
+}
+
+#[test]
+fn write_items_back_to_back() {
+    let mut expected = rlp::encode(&"cat");
+    expected.extend(rlp::encode(&0x0400u32));
+    expected.extend(&[0xc2, 0x01, 0x02]);
+    expected.extend(rlp::encode(&vec![0xab_u8; 1024]));
+    assert_eq!(export(), expected);
+}
+
+#[test]
+fn read_items() {
+    let bytes = export();
+    let items: Vec<_> = RlpReader::new(&bytes[..]).map(Result::unwrap).collect();
+    assert_eq!(items, vec![
+        rlp::encode(&"cat"),
+        rlp::encode(&0x0400u32),
+        vec![0xc2, 0x01, 0x02],
+        rlp::encode(&vec![0xab_u8; 1024])
+    ]);
+}
+
+#[test]
+fn read_items_with_short_reads() {
+    let bytes = export();
+    let reader = RlpReader::new(Trickle {
+        bytes: &bytes,
+        interrupt: false,
+    });
+    let items: Vec<_> = reader.map(Result::unwrap).collect();
+    assert_eq!(items.concat(), bytes);
+    assert_eq!(items.len(), 4);
+}
+
+#[test]
+fn read_values() {
+    let mut bytes = Vec::new();
+    {
+        let mut writer = RlpWriter::new(&mut bytes);
+        for i in 0..300u32 {
+            writer.write(&i).unwrap();
+        }
+    }
+    let values: Vec<u32> = RlpReader::new(&bytes[..]).values().map(Result::unwrap).collect();
+    assert_eq!(values, (0..300).collect::<Vec<_>>());
+
+    let mut reader = RlpReader::new(&bytes[..]);
+    assert_eq!(reader.read::<u32>().unwrap(), Some(0));
+    assert_eq!(reader.read::<u32>().unwrap(), Some(1));
+}
+
+#[test]
+fn empty_reader() {
+    let mut reader = RlpReader::new(&[][..]);
+    assert!(reader.read_item().unwrap().is_none());
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn eof_in_header() {
+    let mut reader = RlpReader::new(&[0xb9, 0x01][..]);
+    match reader.read_item() {
+        Err(ReadError::UnexpectedEof {
+            expected: 3,
+            got: 2,
+        }) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn eof_in_payload() {
+    let mut bytes = rlp::encode(&"cat");
+    bytes.extend(&[0x83, b'd', b'o']);
+    let mut reader = RlpReader::new(&bytes[..]);
+    assert_eq!(reader.read::<String>().unwrap(), Some("cat".to_string()));
+    let err = reader.read_item().unwrap_err();
+    assert_eq!(err.to_string(), "unexpected end of file in the middle of an item: expected 4 bytes, got 3");
+}
+
+#[test]
+fn invalid_item() {
+    let mut reader = RlpReader::new(&[0xb8, 0x05, 1, 2, 3, 4, 5][..]);
+    match reader.read_item() {
+        Err(ReadError::Decoder(DecoderError::RlpInvalidIndirection)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let mut reader = RlpReader::new(&[0xc1, 0x01][..]);
+    match reader.read::<u8>() {
+        Err(ReadError::Decoder(DecoderError::RlpExpectedToBeData)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
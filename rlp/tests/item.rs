@@ -0,0 +1,95 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use primitives::{H256, U256};
+use rlp::{DecoderError, Rlp, RlpItem, RlpStream};
+
+fn sample() -> RlpItem {
+    RlpItem::List(vec![
+        RlpItem::from(7u64),
+        RlpItem::from("cat"),
+        RlpItem::List(vec![RlpItem::from(H256::zero()), RlpItem::List(vec![])]),
+        RlpItem::from(vec![0x01u8, 0x02]),
+    ])
+}
+
+#[test]
+fn encode_and_decode() {
+    let mut stream = RlpStream::new_list(4);
+    stream.append(&7u64).append(&"cat");
+    stream.begin_list(2).append(&H256::zero()).begin_list(0);
+    stream.append(&vec![0x01u8, 0x02]);
+    let expected = stream.out();
+
+    assert_eq!(rlp::encode(&sample()), expected);
+    assert_eq!(rlp::decode::<RlpItem>(&expected), Ok(sample()));
+}
+
+#[test]
+fn conversions() {
+    assert_eq!(RlpItem::from(0u8), RlpItem::Data(vec![]));
+    assert_eq!(RlpItem::from(0x0400u32), RlpItem::Data(vec![0x04, 0x00]));
+    assert_eq!(RlpItem::from(true), RlpItem::Data(vec![0x01]));
+    assert_eq!(RlpItem::from(U256::from(0x0100)), RlpItem::Data(vec![0x01, 0x00]));
+    assert_eq!(RlpItem::from("dog".to_string()), RlpItem::Data(b"dog".to_vec()));
+    assert_eq!(RlpItem::from(&b"dog"[..]), RlpItem::Data(b"dog".to_vec()));
+    assert_eq!(RlpItem::from(vec![RlpItem::from(1u8)]), RlpItem::List(vec![RlpItem::Data(vec![1])]));
+    assert_eq!(
+        RlpItem::from_value(&vec![vec![1u8], vec![]]),
+        RlpItem::List(vec![RlpItem::Data(vec![1]), RlpItem::Data(vec![])])
+    );
+
+    assert_eq!(sample()[0].as_val::<u64>(), Ok(7));
+    assert_eq!(sample()[1].as_val::<String>(), Ok("cat".to_string()));
+    assert_eq!(sample()[&[2, 0][..]].as_val::<H256>(), Ok(H256::zero()));
+}
+
+#[test]
+fn index_by_path() {
+    let mut item = sample();
+    assert_eq!(item.at_path(&[]), Some(&item));
+    assert_eq!(item.at_path(&[2, 1]), Some(&RlpItem::List(vec![])));
+    assert_eq!(item.at_path(&[2, 2]), None);
+    assert_eq!(item.at_path(&[1, 0]), None);
+    assert_eq!(item[3].data(), Some(&[0x01, 0x02][..]));
+    assert!(item[2].is_list());
+    assert_eq!(item[2].items().map(<[_]>::len), Some(2));
+
+    *item.at_path_mut(&[2, 1]).unwrap() = RlpItem::from("dog");
+    assert_eq!(item[&[2, 1][..]], RlpItem::from("dog"));
+}
+
+#[test]
+#[should_panic(expected = "No item at [1, 0]")]
+fn index_into_data() {
+    let _ = &sample()[&[1, 0][..]];
+}
+
+#[test]
+fn display_like_rlp() {
+    let item = sample();
+    let encoded = rlp::encode(&item);
+    assert_eq!(item.to_string(), Rlp::new(&encoded).to_string());
+    assert_eq!(
+        item.to_string(),
+        "[\"0x07\", \"0x636174\", [\"0x0000000000000000000000000000000000000000000000000000000000000000\", []], \"0x0102\"]"
+    );
+}
+
+#[test]
+fn decode_malformed() {
+    // The declared payload ends in the middle of the second item.
+    let data = [0xc3, 0x01, 0x83, b'c', b'a', b't'];
+    assert_eq!(
+        rlp::decode::<RlpItem>(&data),
+        Err(DecoderError::RlpIsTooShort {
+            expected: 4,
+            got: 2,
+        })
+    );
+}
@@ -0,0 +1,49 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use primitives::{H256, U256};
+use rlp::{rlp, rlp_item, RlpItem, RlpStream};
+
+#[test]
+fn empty_list() {
+    assert_eq!(rlp![], vec![0xc0]);
+    assert_eq!(rlp_item![], RlpItem::List(vec![]));
+    assert_eq!(rlp![[], [[]]], vec![0xc3, 0xc0, 0xc1, 0xc0]);
+}
+
+#[test]
+fn values() {
+    assert_eq!(rlp!["cat", "dog"], vec![0xc8, 0x83, b'c', b'a', b't', 0x83, b'd', b'o', b'g']);
+    assert_eq!(rlp![0u8, 0x0400u16, true], vec![0xc5, 0x80, 0x82, 0x04, 0x00, 0x01]);
+
+    let name = String::from("cat");
+    let number = U256::from(0x10);
+    assert_eq!(rlp![name, number + 1, vec![1u8, 2]], vec![0xc8, 0x83, b'c', b'a', b't', 0x11, 0x82, 0x01, 0x02]);
+}
+
+#[test]
+fn bytes() {
+    assert_eq!(rlp![bytes!()], vec![0xc1, 0x80]);
+    assert_eq!(rlp![bytes!(0x05)], vec![0xc1, 0x05]);
+    assert_eq!(rlp![bytes!(0x01, 0x02,)], vec![0xc3, 0x82, 0x01, 0x02]);
+    assert_eq!(rlp_item![bytes!(0x01, 0x02)][0], RlpItem::Data(vec![0x01, 0x02]));
+}
+
+#[test]
+fn nested_lists() {
+    let mut stream = RlpStream::new_list(3);
+    stream.append(&1u64);
+    stream.begin_list(2).append(&H256::zero()).begin_list(1).append(&"cat");
+    stream.begin_list(0);
+    let expected = stream.out();
+    assert_eq!(rlp![1u64, [H256::zero(), ["cat"]], []], expected);
+
+    let item = rlp_item![1u64, [H256::zero(), ["cat"]], []];
+    assert_eq!(item[&[1, 1, 0][..]], RlpItem::from("cat"));
+    assert_eq!(rlp::encode(&item), expected);
+}
@@ -0,0 +1,87 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(feature = "mmap")]
+
+use rlp::{DecoderError, RlpFile, RlpStream};
+use std::fs;
+use std::path::PathBuf;
+
+/// A file in the temporary directory removed on drop.
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new(name: &str, contents: &[u8]) -> Self {
+        let path = std::env::temp_dir().join(format!("rlp-{}-{}", std::process::id(), name));
+        fs::write(&path, contents).unwrap();
+        TempFile(path)
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+#[test]
+fn navigate_single_item() {
+    let mut stream = RlpStream::new_list(3);
+    stream.append(&"cat").append(&0x0400u32).begin_list(1).append(&vec![0xab_u8; 1024]);
+    let file = TempFile::new("single", &stream.out());
+
+    let file = RlpFile::open(&file.0).unwrap();
+    let rlp = file.rlp();
+    assert_eq!(rlp.item_count(), Ok(3));
+    assert_eq!(rlp.val_at::<String>(0), Ok("cat".to_string()));
+    assert_eq!(rlp.val_at::<u32>(1), Ok(0x0400));
+    assert_eq!(rlp.at(2).unwrap().val_at::<Vec<u8>>(0), Ok(vec![0xab; 1024]));
+}
+
+#[test]
+fn iterate_items() {
+    let mut bytes = rlp::encode(&"cat");
+    bytes.extend(rlp::encode_list::<u64, _>(&[1, 2, 3]));
+    bytes.extend(rlp::encode(&"dog"));
+    let file = TempFile::new("items", &bytes);
+
+    let file = RlpFile::open(&file.0).unwrap();
+    let items: Vec<_> = file.items().map(Result::unwrap).collect();
+    assert_eq!(items.len(), 3);
+    assert_eq!(items[0].as_val::<String>(), Ok("cat".to_string()));
+    assert_eq!(items[1].as_list::<u64>(), Ok(vec![1, 2, 3]));
+    assert_eq!(items[2].as_val::<String>(), Ok("dog".to_string()));
+}
+
+#[test]
+fn truncated_item() {
+    let mut bytes = rlp::encode(&"cat");
+    bytes.extend(&[0x83, b'd', b'o']);
+    let file = TempFile::new("truncated", &bytes);
+
+    let file = RlpFile::open(&file.0).unwrap();
+    let mut items = file.items();
+    assert_eq!(items.next().unwrap().unwrap().as_val::<String>(), Ok("cat".to_string()));
+    assert_eq!(
+        items.next().unwrap().unwrap_err(),
+        DecoderError::RlpIsTooShort {
+            expected: 4,
+            got: 3,
+        }
+    );
+    assert!(items.next().is_none());
+}
+
+#[test]
+fn empty_file() {
+    let file = TempFile::new("empty", &[]);
+
+    let file = RlpFile::open(&file.0).unwrap();
+    assert!(file.as_bytes().is_empty());
+    assert!(file.items().next().is_none());
+}
@@ -0,0 +1,62 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use rlp::{DecoderError, OptionAsEmptyData, OptionAsTrailing, Rlp, RlpStream};
+
+#[test]
+fn empty_data_round_trip() {
+    for value in &[None, Some("cat".to_string())] {
+        let encoded = rlp::encode(&OptionAsEmptyData(value.clone()));
+        assert_eq!(rlp::decode(&encoded), Ok(OptionAsEmptyData(value.clone())));
+    }
+    assert_eq!(rlp::encode(&OptionAsEmptyData::<String>(None)), vec![0x80]);
+    assert_eq!(rlp::encode(&OptionAsEmptyData(Some("cat"))), vec![0x83, b'c', b'a', b't']);
+}
+
+#[test]
+fn empty_data_in_list() {
+    let mut stream = RlpStream::new_list(3);
+    stream.append(&OptionAsEmptyData(Some(7u8)));
+    OptionAsEmptyData::append(&mut stream, &None::<u8>);
+    OptionAsEmptyData::append(&mut stream, &Some(8u8));
+    let out = stream.out();
+    assert_eq!(out, vec![0xc3, 0x07, 0x80, 0x08]);
+
+    let decoded: Vec<Option<u8>> =
+        rlp::decode_list::<OptionAsEmptyData<u8>>(&out).unwrap().into_iter().map(Option::from).collect();
+    assert_eq!(decoded, vec![Some(7), None, Some(8)]);
+}
+
+#[test]
+fn empty_data_is_ambiguous_with_empty_values() {
+    assert_eq!(rlp::decode(&rlp::encode(&OptionAsEmptyData(Some(0u64)))), Ok(OptionAsEmptyData::<u64>(None)));
+    assert_eq!(rlp::decode::<OptionAsEmptyData<Vec<u8>>>(&[0xc0]), Err(DecoderError::RlpExpectedToBeData));
+}
+
+#[test]
+fn trailing_count() {
+    assert_eq!(OptionAsTrailing::count(&[]), 0);
+    assert_eq!(OptionAsTrailing::count(&[true, true]), 2);
+    assert_eq!(OptionAsTrailing::count(&[true, false]), 1);
+    assert_eq!(OptionAsTrailing::count(&[false, false]), 0);
+}
+
+#[test]
+#[should_panic(expected = "a trailing None cannot be followed by Some")]
+fn trailing_none_followed_by_some() {
+    OptionAsTrailing::count(&[false, true]);
+}
+
+#[test]
+fn trailing_decode() {
+    let data = vec![0xc2, 0x01, 0x02];
+    let rlp = Rlp::new(&data);
+    assert_eq!(OptionAsTrailing::decode::<u8>(&rlp, 1), Ok(Some(2)));
+    assert_eq!(OptionAsTrailing::decode::<u8>(&rlp, 2), Ok(None));
+    assert_eq!(OptionAsTrailing::decode::<u8>(&Rlp::new(&[0x80]), 0), Err(DecoderError::RlpExpectedToBeList));
+}
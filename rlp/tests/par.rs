@@ -0,0 +1,83 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![cfg(feature = "rayon")]
+
+use rlp::{DecoderError, Rlp, RlpStream};
+
+#[test]
+fn same_as_sequential_decoding() {
+    let values: Vec<(u64, String, u8)> = (0..10_000u64).map(|i| (i * i, format!("item {}", i), i as u8)).collect();
+    let mut stream = RlpStream::new_list(values.len());
+    for (number, name, byte) in &values {
+        stream.begin_list(3).append(number).append(name).append(byte);
+    }
+    let bytes = stream.out();
+
+    let rlp = Rlp::new(&bytes);
+    let decoded = rlp.par_as_list::<(u64, String, u8)>().unwrap();
+    assert_eq!(decoded, values);
+    assert_eq!(rlp.as_list::<(u64, String, u8)>().unwrap(), decoded);
+}
+
+#[test]
+fn empty_list() {
+    let rlp = Rlp::new(&[0xc0]);
+    assert_eq!(rlp.par_as_list::<u64>(), Ok(vec![]));
+}
+
+#[test]
+fn not_a_list() {
+    let rlp = Rlp::new(&[0x83, b'c', b'a', b't']);
+    assert_eq!(rlp.par_as_list::<u64>(), Err(DecoderError::RlpExpectedToBeList));
+}
+
+#[test]
+fn lowest_index_error_is_returned() {
+    let mut stream = RlpStream::new_list(10_000);
+    for i in 0..10_000u64 {
+        match i {
+            5000 => stream.append(&"too long for u64 after all"),
+            7000 => stream.begin_list(0),
+            _ => stream.append(&i),
+        };
+    }
+    let bytes = stream.out();
+
+    for _ in 0..10 {
+        assert_eq!(
+            Rlp::new(&bytes).par_as_list::<u64>(),
+            Err(DecoderError::IntegerOverflow {
+                type_name: "u64",
+                len: 26,
+            })
+        );
+    }
+}
+
+#[test]
+fn malformed_item_after_invalid_one() {
+    // The second item is invalid for u8, and the last one is truncated.
+    let bytes = [0xc6, 0x01, 0x82, 0x01, 0x02, 0x83, 0x01];
+    assert_eq!(
+        Rlp::new(&bytes).par_as_list::<u8>(),
+        Err(DecoderError::IntegerOverflow {
+            type_name: "u8",
+            len: 2,
+        })
+    );
+
+    let bytes = [0xc4, 0x01, 0x02, 0x83, 0x01];
+    assert_eq!(
+        Rlp::new(&bytes).par_as_list::<u8>(),
+        Err(DecoderError::RlpIsTooShort {
+            expected: 1,
+            got: 0,
+        })
+    );
+}
@@ -0,0 +1,117 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use primitives::U256;
+use rlp::{Decodable, DecoderError, DecodingProfile, Rlp};
+
+const PROFILES: [DecodingProfile; 3] = [DecodingProfile::Strict, DecodingProfile::Ethereum, DecodingProfile::Legacy];
+
+fn decode<T: Decodable>(bytes: &[u8], profile: DecodingProfile) -> Result<T, DecoderError> {
+    rlp::decode_with_profile(bytes, profile)
+}
+
+#[test]
+fn default_profile_is_strict() {
+    assert_eq!(DecodingProfile::default(), DecodingProfile::Strict);
+    assert_eq!(Rlp::new(&[0x80]).profile(), DecodingProfile::Strict);
+    assert_eq!(rlp::decode::<bool>(&[0x80]), decode::<bool>(&[0x80], DecodingProfile::Strict));
+}
+
+#[test]
+fn bool_in_every_profile() {
+    use DecodingProfile::*;
+
+    let too_short = Err(DecoderError::RlpIsTooShort {
+        expected: 1,
+        got: 0,
+    });
+    let not_bool = Err(DecoderError::InvalidBool {
+        byte: 2,
+    });
+    assert_eq!(decode::<bool>(&[0x00], Strict), Ok(false));
+    assert_eq!(decode::<bool>(&[0x80], Strict), too_short);
+    assert_eq!(decode::<bool>(&[0x02], Strict), not_bool);
+
+    assert_eq!(decode::<bool>(&[0x00], Ethereum), Err(DecoderError::LeadingZeroInteger));
+    assert_eq!(decode::<bool>(&[0x80], Ethereum), Ok(false));
+    assert_eq!(decode::<bool>(&[0x02], Ethereum), not_bool);
+
+    assert_eq!(decode::<bool>(&[0x00], Legacy), Ok(false));
+    assert_eq!(decode::<bool>(&[0x80], Legacy), Ok(false));
+    assert_eq!(decode::<bool>(&[0x02], Legacy), Ok(true));
+
+    for &profile in &PROFILES {
+        assert_eq!(decode::<bool>(&[0x01], profile), Ok(true));
+        assert_eq!(decode::<bool>(&[0x82, 0x00, 0x01], profile), Err(DecoderError::IntegerOverflow {
+            type_name: "bool",
+            len: 2,
+        }));
+    }
+}
+
+#[test]
+fn leading_zeros_are_ignored_only_by_legacy() {
+    for &profile in &[DecodingProfile::Strict, DecodingProfile::Ethereum] {
+        assert_eq!(decode::<u8>(&[0x00], profile), Err(DecoderError::LeadingZeroInteger));
+        assert_eq!(decode::<u16>(&[0x82, 0x00, 0x01], profile), Err(DecoderError::LeadingZeroInteger));
+        assert_eq!(decode::<u64>(&[0x82, 0x00, 0x01], profile), Err(DecoderError::LeadingZeroInteger));
+        assert_eq!(decode::<U256>(&[0x82, 0x00, 0x01], profile), Err(DecoderError::LeadingZeroInteger));
+    }
+
+    let legacy = DecodingProfile::Legacy;
+    assert_eq!(decode::<u8>(&[0x00], legacy), Ok(0));
+    assert_eq!(decode::<u8>(&[0x82, 0x00, 0x01], legacy), Ok(1));
+    assert_eq!(decode::<u16>(&[0x83, 0x00, 0x01, 0x02], legacy), Ok(0x0102));
+    assert_eq!(decode::<usize>(&[0x82, 0x00, 0x00], legacy), Ok(0));
+    assert_eq!(decode::<U256>(&[0x82, 0x00, 0x01], legacy), Ok(U256::from(1)));
+    assert_eq!(decode::<u16>(&[0x84, 0x00, 0x01, 0x02, 0x03], legacy), Err(DecoderError::IntegerOverflow {
+        type_name: "u16",
+        len: 3,
+    }));
+}
+
+#[test]
+fn nul_in_string_is_rejected_only_by_strict() {
+    let data = [0x83, b'c', 0x00, b't'];
+    assert_eq!(decode::<String>(&data, DecodingProfile::Strict), Err(DecoderError::RlpNullTerminatedString));
+    assert_eq!(decode::<String>(&data, DecodingProfile::Ethereum), Ok("c\0t".to_string()));
+    assert_eq!(decode::<String>(&data, DecodingProfile::Legacy), Ok("c\0t".to_string()));
+
+    for &profile in &PROFILES {
+        assert_eq!(decode::<String>(&[0x81, 0xff], profile), Err(DecoderError::InvalidUtf8 {
+            valid_up_to: 0,
+        }));
+    }
+}
+
+#[test]
+fn profile_is_inherited_by_items() {
+    let data = vec![0xc6, 0xc2, 0x00, 0x80, 0x82, 0x00, 0x07];
+    let rlp = Rlp::with_profile(&data, DecodingProfile::Legacy);
+
+    assert_eq!(rlp.at(0).unwrap().profile(), DecodingProfile::Legacy);
+    assert_eq!(rlp.val_at::<(bool, bool, u8)>(0).err(), Some(DecoderError::RlpIncorrectListLen {
+        expected: 3,
+        got: 2,
+    }));
+    assert_eq!(rlp.at(0).unwrap().as_list::<bool>(), Ok(vec![false, false]));
+    assert_eq!(rlp.val_at::<u8>(1), Ok(7));
+    assert!(rlp.iter().all(|item| item.profile() == DecodingProfile::Legacy));
+
+    let mut cursor = rlp.cursor().unwrap();
+    assert_eq!(cursor.next_list::<bool>(), Ok(vec![false, false]));
+    assert_eq!(cursor.next::<u8>(), Ok(7));
+    assert_eq!(cursor.finish(), Ok(()));
+
+    let strict = Rlp::new(&data);
+    assert_eq!(strict.val_at::<u16>(1), Err(DecoderError::LeadingZeroInteger));
+    assert_eq!(strict.at(0).unwrap().as_list::<bool>().err(), Some(DecoderError::RlpIsTooShort {
+        expected: 1,
+        got: 0,
+    }));
+}
@@ -0,0 +1,160 @@
+// Copyright 2020 Kodebox, Inc.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use primitives::{H160, H256, U256};
+use rlp::{DecoderError, DecodingProfile, ParseSchemaError, Rlp, RlpSchema, RlpStream, Schema, SchemaMismatch};
+
+fn mismatch(path: &[usize], error: DecoderError) -> SchemaMismatch {
+    SchemaMismatch {
+        path: path.to_vec(),
+        error,
+    }
+}
+
+fn transactions(to: &[u8], nonce: u64, accessed: &[H160]) -> Vec<u8> {
+    let mut stream = RlpStream::new_list(1);
+    stream.begin_list(3).append(&to).append(&nonce).append_list(accessed);
+    stream.out()
+}
+
+#[test]
+fn parse_text_form() {
+    let schema: Schema = "list of [bytes32, uint, list of bytes20]".parse().unwrap();
+    assert_eq!(
+        schema,
+        Schema::ListOf(Box::new(Schema::List(vec![
+            Schema::FixedBytes(32),
+            Schema::Uint(32),
+            Schema::ListOf(Box::new(Schema::FixedBytes(20))),
+        ])))
+    );
+    assert_eq!(schema.to_string(), "list of [bytes32, uint256, list of bytes20]");
+
+    let schema: Schema = " [ any,bool , [], optional string, bytes, uint64 ] ".parse().unwrap();
+    assert_eq!(schema.to_string(), "[any, bool, [], optional string, bytes, uint64]");
+    assert_eq!(schema.to_string().parse(), Ok(schema));
+}
+
+#[test]
+fn parse_errors() {
+    let error = |position, reason| {
+        Err(ParseSchemaError {
+            position,
+            reason,
+        })
+    };
+    assert_eq!("".parse::<Schema>(), error(0, "Expected a schema"));
+    assert_eq!("list bytes".parse::<Schema>(), error(5, "Expected 'of'"));
+    assert_eq!("[bytes uint]".parse::<Schema>(), error(7, "Expected ',' or ']'"));
+    assert_eq!("[bytes".parse::<Schema>(), error(6, "Expected ',' or ']'"));
+    assert_eq!("uint7".parse::<Schema>(), error(0, "Invalid number of bits"));
+    assert_eq!("bytes0".parse::<Schema>(), error(0, "Invalid number of bytes"));
+    assert_eq!("integer".parse::<Schema>(), error(0, "Unknown schema"));
+    assert_eq!("bytes ]".parse::<Schema>(), error(6, "Unexpected trailing characters"));
+}
+
+#[test]
+fn validate_matching_rlp() {
+    let schema: Schema = "list of [bytes32, uint, list of bytes20]".parse().unwrap();
+    let data = transactions(&[0xab; 32], 7, &[H160::zero(), H160::repeat_byte(1)]);
+    assert_eq!(schema.validate(&Rlp::new(&data)), Ok(()));
+}
+
+#[test]
+fn validate_reports_every_mismatch_with_path() {
+    let schema: Schema = "list of [bytes32, uint8, list of bytes20]".parse().unwrap();
+    let mut stream = RlpStream::new_list(1);
+    stream.begin_list(3).append(&vec![0xabu8; 31]).append(&0x100u64).begin_list(2).append(&"cat").append(&H160::zero());
+    let data = stream.out();
+
+    assert_eq!(
+        schema.validate(&Rlp::new(&data)),
+        Err(vec![
+            mismatch(&[0, 0], DecoderError::RlpIsTooShort {
+                expected: 32,
+                got: 31
+            }),
+            mismatch(&[0, 1], DecoderError::IntegerOverflow {
+                type_name: "uint",
+                len: 2
+            }),
+            mismatch(&[0, 2, 0], DecoderError::RlpIsTooShort {
+                expected: 20,
+                got: 3
+            }),
+        ])
+    );
+}
+
+#[test]
+fn validate_list_shape() {
+    let schema: Schema = "[uint, optional bytes]".parse().unwrap();
+    assert_eq!(
+        schema.validate(&Rlp::new(&rlp::encode(&1u8))),
+        Err(vec![mismatch(&[], DecoderError::RlpExpectedToBeList)])
+    );
+    assert_eq!(
+        schema.validate(&Rlp::new(&rlp::encode_list::<u8, _>(&[1, 2, 3]))),
+        Err(vec![
+            mismatch(&[1], DecoderError::RlpExpectedToBeList),
+            mismatch(&[], DecoderError::RlpIncorrectListLen {
+                expected: 2,
+                got: 3
+            }),
+        ])
+    );
+
+    let mut stream = RlpStream::new_list(2);
+    stream.append(&1u8).append_list::<u8, _>(&[1, 2]);
+    assert_eq!(
+        schema.validate(&Rlp::new(&stream.out())),
+        Err(vec![mismatch(&[1], DecoderError::RlpIncorrectListLen {
+            expected: 1,
+            got: 2
+        })])
+    );
+}
+
+#[test]
+fn validate_malformed_rlp() {
+    let schema: Schema = "list of string".parse().unwrap();
+    let data = vec![0xc8, 0x83, b'c', b'a', b't', 0x85, b'd', b'o', b'g'];
+    assert_eq!(
+        schema.validate(&Rlp::new(&data)),
+        Err(vec![mismatch(&[], DecoderError::RlpIsTooShort {
+            expected: 1,
+            got: 0
+        })])
+    );
+}
+
+#[test]
+fn validate_follows_profile() {
+    let schema: Schema = "[bool, uint16]".parse().unwrap();
+    let data = vec![0xc4, 0x80, 0x82, 0x00, 0x01];
+    assert_eq!(
+        schema.validate(&Rlp::new(&data)),
+        Err(vec![
+            mismatch(&[0], DecoderError::RlpIsTooShort {
+                expected: 1,
+                got: 0
+            }),
+            mismatch(&[1], DecoderError::LeadingZeroInteger),
+        ])
+    );
+    assert_eq!(schema.validate(&Rlp::with_profile(&data, DecodingProfile::Legacy)), Ok(()));
+}
+
+#[test]
+fn schema_of_types() {
+    assert_eq!(<(u8, H256, Option<U256>)>::rlp_schema().to_string(), "[uint8, bytes32, optional uint256]");
+    assert_eq!(<Vec<Vec<u8>>>::rlp_schema().to_string(), "list of bytes");
+
+    let data = rlp::encode(&(1u8, H256::zero(), Some(U256::from(3))));
+    assert_eq!(<(u8, H256, Option<U256>)>::rlp_schema().validate(&Rlp::new(&data)), Ok(()));
+}
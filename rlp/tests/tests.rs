@@ -9,7 +9,7 @@
 
 use hex_literal::hex;
 use primitives::{H160, U256};
-use rlp::{Decodable, DecoderError, Encodable, Rlp, RlpStream};
+use rlp::{Decodable, DecoderError, Encodable, PayloadInfo, Rlp, RlpStream};
 use std::str::FromStr;
 use std::{cmp, fmt};
 
@@ -60,6 +60,18 @@ fn rlp_at_err() {
     }
 }
 
+#[test]
+fn rlp_at_does_not_read_past_payload() {
+    // The list declares 4 bytes of payload, but its second item ends 1 byte past them.
+    let data = vec![0xc4, 0x81, b'a', 0x82, b'b', b'c'];
+    let rlp = Rlp::new(&data);
+    assert_eq!(rlp.at(0).unwrap().as_raw(), &[0x81, b'a']);
+    assert_eq!(rlp.at(1).unwrap_err(), DecoderError::RlpIsTooShort {
+        expected: 6,
+        got: 5
+    });
+}
+
 #[test]
 fn rlp_iter() {
     let data = vec![0xc8, 0x83, b'c', b'a', b't', 0x83, b'd', b'o', b'g'];
@@ -84,6 +96,46 @@ fn rlp_iter() {
     }
 }
 
+#[test]
+fn rlp_iter_exact_size_and_reversed() {
+    let data = rlp::encode_list::<u64, _>(&[1, 2, 3, 4, 5]);
+    let rlp = Rlp::new(&data);
+
+    let mut iter = rlp.iter();
+    assert_eq!(iter.len(), 5);
+    assert_eq!(iter.next().unwrap().as_val::<u64>(), Ok(1));
+    assert_eq!(iter.len(), 4);
+    assert_eq!(iter.next_back().unwrap().as_val::<u64>(), Ok(5));
+    assert_eq!(iter.len(), 3);
+    assert_eq!(iter.next().unwrap().as_val::<u64>(), Ok(2));
+    assert_eq!(iter.next_back().unwrap().as_val::<u64>(), Ok(4));
+    assert_eq!(iter.next().unwrap().as_val::<u64>(), Ok(3));
+    assert_eq!(iter.len(), 0);
+    assert!(iter.next().is_none());
+    assert!(iter.next_back().is_none());
+
+    let reversed: Vec<u64> = rlp.iter().rev().map(|item| item.as_val().unwrap()).collect();
+    assert_eq!(reversed, vec![5, 4, 3, 2, 1]);
+
+    assert_eq!(rlp.item_count(), Ok(5));
+    assert_eq!(rlp.iter().len(), 5);
+}
+
+#[test]
+fn rlp_iter_stops_at_malformed_item() {
+    // The declared payload ends in the middle of the last item.
+    let data = vec![0xc5, 0x01, 0x02, 0x83, b'c', b'a', b't'];
+    let rlp = Rlp::new(&data);
+    assert_eq!(rlp.iter().len(), 2);
+    assert_eq!(rlp.iter().count(), 2);
+    assert_eq!(rlp.iter().rev().count(), 2);
+    assert_eq!(rlp.item_count(), Ok(2));
+
+    let data = vec![0x83, b'c', b'a', b't'];
+    assert_eq!(Rlp::new(&data).iter().len(), 0);
+    assert!(Rlp::new(&data).iter().next_back().is_none());
+}
+
 struct ETestPair<T>(T, Vec<u8>)
 where
     T: Encodable;
@@ -243,11 +295,57 @@ fn run_decode_tests_list<T>(tests: Vec<VDTestPair<T>>)
 where
     T: Decodable + fmt::Debug + cmp::Eq, {
     for t in &tests {
-        let res: Vec<T> = rlp::decode_list(&t.1);
-        assert_eq!(res, t.0);
+        let res: Result<Vec<T>, DecoderError> = rlp::decode_list(&t.1);
+        assert_eq!(res.as_ref(), Ok(&t.0));
     }
 }
 
+#[test]
+fn decode_list_fails_on_malformed_input() {
+    assert_eq!(rlp::decode_list::<u8>(&[0xc0]), Ok(vec![]));
+    assert_eq!(rlp::decode_list::<u8>(&[0x83, b'c', b'a', b't']), Err(DecoderError::RlpExpectedToBeList));
+    assert_eq!(
+        rlp::decode_list::<u8>(&[0xc3, 0x01, 0x02]),
+        Err(DecoderError::RlpIsTooShort {
+            expected: 1,
+            got: 0,
+        })
+    );
+    // The declared payload ends in the middle of the second item.
+    assert_eq!(
+        rlp::decode_list::<Vec<u8>>(&[0xc3, 0x01, 0x82, 0x01, 0x02]),
+        Err(DecoderError::RlpIsTooShort {
+            expected: 3,
+            got: 2,
+        })
+    );
+    assert_eq!(rlp::decode_list::<u8>(&[0xc2, 0x01, 0xc0]), Err(DecoderError::RlpExpectedToBeData));
+}
+
+#[test]
+fn decode_list_exact() {
+    let data = rlp::encode_list::<u64, _>(&[1, 2, 3]);
+    assert_eq!(rlp::decode_list_exact::<u64>(&data, 3), Ok(vec![1, 2, 3]));
+    assert_eq!(
+        rlp::decode_list_exact::<u64>(&data, 4),
+        Err(DecoderError::RlpIncorrectListLen {
+            expected: 4,
+            got: 3,
+        })
+    );
+}
+
+#[test]
+fn encode_iter() {
+    let values = vec![1u64, 0x0400, 0];
+    assert_eq!(rlp::encode_iter::<u64, _, _>(values.iter()), rlp::encode_list::<u64, _>(&values));
+    assert_eq!(rlp::encode_iter::<u64, _, _>(values.clone()), rlp::encode_list::<u64, _>(&values));
+    assert_eq!(rlp::encode_iter::<u64, _, _>(Vec::<u64>::new()), vec![0xc0]);
+
+    let long: Vec<String> = (0..100).map(|i| format!("item {}", i)).collect();
+    assert_eq!(rlp::encode_iter::<String, _, _>(&long), rlp::encode_list::<String, _>(&long));
+}
+
 #[test]
 fn decode_untrusted_bool() {
     let tests = vec![DTestPair(false, vec![0x00]), DTestPair(true, vec![0x01])];
@@ -468,6 +566,33 @@ fn rlp_stream_size_limit() {
     }
 }
 
+#[cfg(target_pointer_width = "64")]
+#[test]
+fn rlp_stream_estimate_size_beyond_u32() {
+    // The payload is never materialized; only the header arithmetic is checked.
+    let stream = RlpStream::new_list(1);
+    assert_eq!(stream.estimate_size(0xffff_ffff), 1 + 4 + 0xffff_ffff);
+    assert_eq!(stream.estimate_size(0x1_0000_0000), 1 + 5 + 0x1_0000_0000);
+    assert_eq!(stream.estimate_size(0x0100_0000_0000_0000), 1 + 8 + 0x0100_0000_0000_0000);
+
+    let mut stream = RlpStream::new_list(1);
+    stream.begin_list(1);
+    // the inner list needs 4 length bytes, which pushes the outer payload over u32::MAX
+    assert_eq!(stream.estimate_size(0xffff_fffb), 1 + 5 + 1 + 4 + 0xffff_fffb);
+}
+
+#[cfg(target_pointer_width = "64")]
+#[test]
+fn rlp_payload_info_beyond_u32() {
+    let info = PayloadInfo::from(&[0xb7 + 5, 0x01, 0x00, 0x00, 0x00, 0x00]).unwrap();
+    assert_eq!(info.header_len, 6);
+    assert_eq!(info.value_len, 0x1_0000_0000);
+
+    let info = PayloadInfo::from(&[0xf7 + 8, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08]).unwrap();
+    assert_eq!(info.header_len, 9);
+    assert_eq!(info.value_len, 0x0102_0304_0506_0708);
+}
+
 #[test]
 fn rlp_stream_unbounded_list() {
     let mut stream = RlpStream::new();